@@ -0,0 +1,103 @@
+use crate::constraints::Constraints;
+use crate::graph::Graph;
+use crate::trace;
+use crate::tree::Tree;
+use crate::{Max, PlacementsTree, RecalcAt, Stats};
+
+#[derive(Clone, Debug)]
+pub struct Builder {
+    n: usize,
+    k: usize,
+    min_k: Option<usize>,
+    key: usize,
+    end: Option<usize>,
+    precedence: Vec<(usize, usize)>,
+    required: Vec<usize>,
+    symmetric: bool,
+}
+
+impl Builder {
+    pub fn new(n: usize, k: usize, key: usize) -> Self {
+        Self {
+            n,
+            k,
+            min_k: None,
+            key,
+            end: Some(key),
+            precedence: Vec::new(),
+            required: Vec::new(),
+            symmetric: false,
+        }
+    }
+
+    pub fn min_k(mut self, min_k: usize) -> Self {
+        self.min_k = Some(min_k);
+        self
+    }
+
+    pub fn end(mut self, end: usize) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    pub fn open(mut self) -> Self {
+        self.end = None;
+        self
+    }
+
+    pub fn precedence<I>(mut self, pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        self.precedence.extend(pairs);
+        self
+    }
+
+    pub fn required<I>(mut self, vertices: I) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        self.required.extend(vertices);
+        self
+    }
+
+    pub fn symmetric(mut self) -> Self {
+        self.symmetric = true;
+        self
+    }
+
+    pub fn build<V, E, D>(self, val: D) -> PlacementsTree<V, E, D>
+    where
+        V: Default + Clone,
+        E: Default + Clone,
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        let _span = trace::span!("build", n = self.n, k = self.k, key = self.key);
+        let mut constraints = Constraints::new(self.n);
+        for &(i, j) in self.precedence.iter() {
+            assert!(j != self.key);
+            assert!(Some(j) != self.end);
+            assert!(Some(i) != self.end || i == self.key);
+            constraints.precede(i, j);
+        }
+        for &v in self.required.iter() {
+            constraints.require(v);
+        }
+        let mut graph = Graph::new(self.n);
+        graph.symmetric = self.symmetric;
+        PlacementsTree {
+            tree: Tree::new(
+                self.k,
+                self.min_k,
+                self.key,
+                self.end,
+                constraints,
+                val,
+                &graph,
+            ),
+            graph,
+            observers: Vec::new(),
+            stats: Stats::default(),
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use crate::max::Max;
+use crate::recalc::RecalcAt;
+use std::ops::Add;
+
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct Capacitated<C, L> {
+    pub cost: C,
+    pub load: L,
+    pub capacity: L,
+}
+
+impl<C, L> Capacitated<C, L> {
+    pub fn new(cost: C, capacity: L) -> Self
+    where
+        L: Default,
+    {
+        Self {
+            cost,
+            load: L::default(),
+            capacity,
+        }
+    }
+}
+
+impl<C, L> Max for Capacitated<C, L>
+where
+    C: Max,
+    L: Max + PartialOrd + Default,
+{
+    fn max() -> Self {
+        Self {
+            cost: C::max(),
+            load: L::max(),
+            capacity: L::default(),
+        }
+    }
+
+    fn is_feasible(&self) -> bool {
+        self.load <= self.capacity
+    }
+}
+
+impl<C, L> Capacitated<C, L>
+where
+    C: Add<Output = C> + Copy + Max,
+    L: Add<Output = L> + PartialOrd + Copy + Max + Default,
+{
+    fn arrive(&self, edge: &C, next: &L) -> Self {
+        if !self.is_feasible() {
+            return Self::max();
+        }
+        let load = self.load + *next;
+        if load > self.capacity {
+            Self::max()
+        } else {
+            Self {
+                cost: self.cost + *edge,
+                load,
+                capacity: self.capacity,
+            }
+        }
+    }
+}
+
+macro_rules! recalc_at {
+    ($($c:ty),*; $ls:tt) => {
+        $(
+            recalc_at!(@load $c; $ls);
+        )*
+    };
+    (@load $c:ty; ($($l:ty),*)) => {
+        $(
+            impl RecalcAt<$l, $c> for Capacitated<$c, $l> {
+                fn recalc_at(
+                    &self,
+                    _depth: usize,
+                    _from: usize,
+                    _to: usize,
+                    _vertex: &$l,
+                    edge: &$c,
+                    next: &$l,
+                ) -> Self {
+                    self.arrive(edge, next)
+                }
+            }
+        )*
+    };
+}
+
+recalc_at!(
+    i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, isize, usize, f32, f64;
+    (i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, isize, usize, f32, f64)
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::update_edges;
+    use crate::{Builder, PlacementsTree};
+
+    #[test]
+    fn recalc_test() {
+        let val = Capacitated::new(0, 5);
+
+        assert!(val.is_feasible());
+        assert!(!Capacitated::<i64, i64>::max().is_feasible());
+
+        let recalc =
+            |val: Capacitated<i64, i64>, next, edge| val.recalc_at(1, 0, 1, &0, &edge, &next);
+        let val = recalc(val, 3, 2);
+
+        assert_eq!(
+            val,
+            Capacitated {
+                cost: 2,
+                load: 3,
+                capacity: 5
+            }
+        );
+        assert_eq!(
+            recalc(val, 2, 1),
+            Capacitated {
+                cost: 3,
+                load: 5,
+                capacity: 5
+            }
+        );
+        assert_eq!(recalc(val, 3, 1), Capacitated::max());
+        assert_eq!(recalc(Capacitated::max(), 0, 1), Capacitated::max());
+        assert_eq!(recalc(Capacitated::max(), 3, 1), Capacitated::max());
+    }
+
+    #[test]
+    fn capacitated_test() {
+        let mut ptree: PlacementsTree<i64, i64, Capacitated<i64, i64>> =
+            PlacementsTree::new(3, 2, 0, Capacitated::new(0, 5));
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as i64);
+        ptree.update_vertex(1, 3);
+        ptree.update_vertex(2, 3);
+        ptree.update_vertex(3, 1);
+
+        assert_eq!(
+            *ptree.shortest().unwrap(),
+            Capacitated {
+                cost: 1 + 7 + 12,
+                load: 4,
+                capacity: 5
+            }
+        );
+        assert_eq!(ptree.update_edge(1, 2, 0), None);
+        assert_eq!(ptree.update_vertex(3, 3), None);
+        assert_eq!(ptree.shortest(), None);
+        assert_eq!(
+            *ptree.update_vertex(1, 2).unwrap(),
+            Capacitated {
+                cost: 1 + 8,
+                load: 5,
+                capacity: 5
+            }
+        );
+    }
+
+    #[test]
+    fn capacitated_deep_test() {
+        let mut ptree: PlacementsTree<i64, i64, Capacitated<i64, i64>> =
+            PlacementsTree::new(4, 3, 0, Capacitated::new(0, 5));
+        ptree.update_vertex(1, 6);
+        ptree.update_vertex(2, 1);
+        update_edges(&mut ptree, |v, u| (v * 5 + u) as i64 % 7);
+
+        assert_eq!(
+            *ptree.shortest().unwrap(),
+            Capacitated {
+                cost: 2 + 2 + 1,
+                load: 1,
+                capacity: 5
+            }
+        );
+    }
+
+    #[test]
+    fn capacitated_open_test() {
+        let mut ptree: PlacementsTree<i64, i64, Capacitated<i64, i64>> =
+            Builder::new(2, 2, 0).open().build(Capacitated::new(0, 5));
+        update_edges(&mut ptree, |_, _| 1);
+        ptree.update_vertex(1, 3);
+
+        assert_eq!(ptree.update_vertex(2, 3), None);
+        assert_eq!(ptree.shortest(), None);
+        assert_eq!(
+            *ptree.update_vertex(2, 2).unwrap(),
+            Capacitated {
+                cost: 2,
+                load: 5,
+                capacity: 5
+            }
+        );
+        assert_eq!(ptree.validate(), vec![]);
+    }
+}
@@ -0,0 +1,103 @@
+#[derive(Clone, Debug)]
+pub struct Constraints {
+    preceding: Vec<Vec<usize>>,
+    required: Vec<bool>,
+}
+
+impl Constraints {
+    pub fn new(n: usize) -> Self {
+        Self {
+            preceding: vec![Vec::new(); n + 1],
+            required: vec![false; n + 1],
+        }
+    }
+
+    pub fn n(&self) -> usize {
+        self.required.len() - 1
+    }
+
+    pub fn precede(&mut self, i: usize, j: usize) {
+        assert!(i <= self.n());
+        assert!(j <= self.n());
+        assert!(i != j);
+        self.preceding[j].push(i);
+    }
+
+    pub fn require(&mut self, v: usize) {
+        assert!(v <= self.n());
+        self.required[v] = true;
+    }
+
+    pub fn is_required(&self, v: usize) -> bool {
+        self.required[v]
+    }
+
+    pub fn has_required(&self) -> bool {
+        self.required.contains(&true)
+    }
+
+    pub fn allows(&self, key: usize, placed: &[bool]) -> bool {
+        self.preceding[key].iter().all(|&i| placed[i])
+    }
+
+    pub fn missing(&self, placed: &[bool]) -> usize {
+        self.required
+            .iter()
+            .zip(placed)
+            .filter(|&(&required, &placed)| required && !placed)
+            .count()
+    }
+
+    pub fn push(&mut self) {
+        self.preceding.push(Vec::new());
+        self.required.push(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_test() {
+        let mut constraints = Constraints::new(3);
+        constraints.precede(1, 3);
+        constraints.precede(2, 3);
+
+        assert!(constraints.allows(1, &[true, false, false, false]));
+        assert!(!constraints.allows(3, &[true, true, false, false]));
+        assert!(constraints.allows(3, &[true, true, true, false]));
+
+        constraints.push();
+
+        assert!(constraints.allows(4, &[true, false, false, false, false]));
+    }
+
+    #[test]
+    fn missing_test() {
+        let mut constraints = Constraints::new(3);
+
+        assert!(!constraints.has_required());
+        assert_eq!(constraints.missing(&[true, false, false, false]), 0);
+
+        constraints.require(1);
+        constraints.require(3);
+
+        assert!(constraints.has_required());
+        assert!(constraints.is_required(3));
+        assert!(!constraints.is_required(2));
+        assert_eq!(constraints.missing(&[true, false, false, false]), 2);
+        assert_eq!(constraints.missing(&[true, false, true, true]), 1);
+
+        constraints.push();
+
+        assert_eq!(constraints.n(), 4);
+        assert_eq!(constraints.missing(&[true, true, false, true, false]), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: i != j")]
+    fn precede_panicked_test() {
+        Constraints::new(3).precede(2, 2);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::apply::Apply;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Set<T>(pub T);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Add<T>(pub T);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Sub<T>(pub T);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Scale(pub f64);
+
+#[derive(Clone, Copy)]
+pub struct With<F>(pub F);
+
+macro_rules! diff {
+    ($($t:ty),*) => {
+        $(
+            impl Apply<Set<$t>> for $t {
+                fn apply(&mut self, diff: Set<$t>) {
+                    *self = diff.0;
+                }
+            }
+
+            impl<F> Apply<With<F>> for $t
+            where
+                F: FnOnce($t) -> $t,
+            {
+                fn apply(&mut self, diff: With<F>) {
+                    *self = diff.0(*self);
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! integer {
+    ($($t:ty),*) => {
+        $(
+            impl Apply<Add<$t>> for $t {
+                fn apply(&mut self, diff: Add<$t>) {
+                    *self = self.saturating_add(diff.0);
+                }
+            }
+
+            impl Apply<Sub<$t>> for $t {
+                fn apply(&mut self, diff: Sub<$t>) {
+                    *self = self.saturating_sub(diff.0);
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! scale {
+    ($($t:ty),*) => {
+        $(
+            impl Apply<Scale> for $t {
+                fn apply(&mut self, diff: Scale) {
+                    *self = if diff.0.is_finite() {
+                        scale(*self as i128, diff.0).clamp(<$t>::MIN as i128, <$t>::MAX as i128) as $t
+                    } else {
+                        (*self as f64 * diff.0).round() as $t
+                    };
+                }
+            }
+        )*
+    };
+}
+
+fn scale(val: i128, factor: f64) -> i128 {
+    let bits = factor.to_bits();
+    let exp = ((bits >> 52) & 0x7ff) as i32;
+    let frac = (bits & ((1 << 52) - 1)) as u128;
+    let (mantissa, exp) = if exp == 0 {
+        (frac, -1074)
+    } else {
+        (frac | 1 << 52, exp - 1075)
+    };
+    let product = val.unsigned_abs() * mantissa;
+    let scaled = if exp >= 0 {
+        if product != 0 && product.leading_zeros() <= exp as u32 {
+            u128::MAX
+        } else {
+            product << exp
+        }
+    } else if exp > -128 {
+        let shift = -exp as u32;
+        let rounded = product >> shift;
+        let rem = product & ((1 << shift) - 1);
+        rounded + (rem >= 1 << (shift - 1)) as u128
+    } else {
+        0
+    };
+    let scaled = scaled.min(i128::MAX as u128) as i128;
+    if (val < 0) != (factor < 0.0) {
+        -scaled
+    } else {
+        scaled
+    }
+}
+
+macro_rules! float {
+    ($($t:ty),*) => {
+        $(
+            impl Apply<Add<$t>> for $t {
+                fn apply(&mut self, diff: Add<$t>) {
+                    *self += diff.0;
+                }
+            }
+
+            impl Apply<Sub<$t>> for $t {
+                fn apply(&mut self, diff: Sub<$t>) {
+                    *self -= diff.0;
+                }
+            }
+
+            impl Apply<Scale> for $t {
+                fn apply(&mut self, diff: Scale) {
+                    *self = (*self as f64 * diff.0) as $t;
+                }
+            }
+        )*
+    };
+}
+
+diff!(i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, isize, usize, f32, f64);
+integer!(i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, isize, usize);
+scale!(i8, u8, i16, u16, i32, u32, i64, u64, isize, usize);
+float!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::update_edges;
+    use crate::PlacementsTree;
+
+    #[test]
+    fn apply_test() {
+        let mut val = 10i64;
+        val.apply(Set(4));
+        assert_eq!(val, 4);
+        val.apply(Add(3));
+        assert_eq!(val, 7);
+        val.apply(Sub(9));
+        assert_eq!(val, -2);
+        val.apply(Scale(2.5));
+        assert_eq!(val, -5);
+        val.apply(With(|val: i64| val * val));
+        assert_eq!(val, 25);
+        val.apply(1);
+        assert_eq!(val, 1);
+
+        let mut val = 250u8;
+        val.apply(Add(10));
+        assert_eq!(val, u8::MAX);
+        val.apply(Sub(u8::MAX));
+        assert_eq!(val, 0);
+
+        let mut val = i64::MAX - 1;
+        val.apply(Scale(1.0));
+        assert_eq!(val, i64::MAX - 1);
+        val.apply(Scale(0.5));
+        assert_eq!(val, i64::MAX / 2);
+        val.apply(Scale(-3.0));
+        assert_eq!(val, i64::MIN);
+
+        let mut val = u64::MAX;
+        val.apply(Scale(0.25));
+        assert_eq!(val, u64::MAX / 4 + 1);
+        val.apply(Scale(-1.0));
+        assert_eq!(val, 0);
+
+        let mut val = 5usize;
+        val.apply(Scale(0.1));
+        assert_eq!(val, 1);
+        val.apply(Scale(1e-300));
+        assert_eq!(val, 0);
+
+        let mut val = 1.5f32;
+        val.apply(Scale(2.0));
+        assert_eq!(val, 3.0);
+        val.apply(Sub(0.5));
+        assert_eq!(val, 2.5);
+    }
+
+    #[test]
+    fn update_test() {
+        let mut ptree: PlacementsTree<i64, i64, i64> = PlacementsTree::new(2, 2, 0, 0);
+        update_edges(&mut ptree, |v, u| Set((v * 4 + u) as i64));
+
+        assert_eq!(*ptree.shortest().unwrap(), 1 + 6 + 8);
+        assert_eq!(*ptree.update_edge(0, 1, Add(2)).unwrap(), 3 + 6 + 8);
+        assert_eq!(*ptree.update_edge(2, 1, Sub(9)).unwrap(), 2 + 4);
+        assert_eq!(*ptree.update_vertex(2, Scale(1.5)).unwrap(), 2 + 4);
+        assert_eq!(
+            *ptree.update_edge(1, 0, With(|edge| edge * 3)).unwrap(),
+            2 + 12
+        );
+        assert_eq!(*ptree.edge(1, 0), 12);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::max::Max;
+use crate::recalc::Recalc;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Hash, Default, Debug)]
+pub struct Dist<T>(pub T);
+
+impl<T> Max for Dist<T>
+where
+    T: Max,
+{
+    fn max() -> Self {
+        Self(T::max())
+    }
+
+    fn is_feasible(&self) -> bool {
+        self.0.is_feasible()
+    }
+}
+
+macro_rules! recalc {
+    ($($t:ty),*) => {
+        $(
+            impl Recalc<$t, $t> for $t {
+                fn recalc(&self, vertex: &$t, edge: &$t) -> Self {
+                    if *self == <$t>::MAX {
+                        return <$t>::MAX;
+                    }
+                    vertex
+                        .checked_add(*edge)
+                        .and_then(|step| self.checked_add(step))
+                        .or_else(|| self.checked_add(*vertex)?.checked_add(*edge))
+                        .unwrap_or(<$t>::MAX)
+                }
+            }
+
+            impl Recalc<$t, $t> for Dist<$t> {
+                fn recalc(&self, vertex: &$t, edge: &$t) -> Self {
+                    Self(self.0.recalc(vertex, edge))
+                }
+            }
+        )*
+    };
+}
+
+recalc!(i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, isize, usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::update_edges;
+    use crate::PlacementsTree;
+
+    #[test]
+    fn recalc_test() {
+        assert_eq!(1i64.recalc(&2, &3), 6);
+        assert_eq!(i64::MAX.recalc(&-2, &-3), i64::MAX);
+        assert_eq!((i64::MAX - 1).recalc(&1, &-1), i64::MAX - 1);
+        assert_eq!((i64::MAX - 1).recalc(&1, &0), i64::MAX);
+        assert_eq!(i64::MIN.recalc(&-1, &0), i64::MAX);
+        assert_eq!(i64::MIN.recalc(&i64::MAX, &i64::MAX), i64::MAX - 1);
+        assert_eq!((-5i64).recalc(&i64::MAX, &i64::MAX), i64::MAX);
+        assert_eq!(250u8.recalc(&3, &3), u8::MAX);
+        assert_eq!(Dist(250u8).recalc(&2, &3), Dist(255));
+        assert_eq!(Dist(1u8).recalc(&2, &3), Dist(6));
+        assert!(Dist(254u8).is_feasible());
+        assert!(!Dist::<u8>::max().is_feasible());
+    }
+
+    #[test]
+    fn dist_test() {
+        let mut ptree: PlacementsTree<u8, u8, Dist<u8>> = PlacementsTree::new(2, 2, 0, Dist(0));
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as u8);
+
+        assert_eq!(*ptree.shortest().unwrap(), Dist(1 + 6 + 8));
+        assert_eq!(*ptree.update_edge(0, 1, 200).unwrap(), Dist(200 + 6 + 8));
+        assert_eq!(ptree.update_edge(1, 2, 50), None);
+        assert_eq!(*ptree.shortest().unwrap(), Dist(2 + 9 + 4));
+        assert_eq!(ptree.update_vertex(0, 255), None);
+        assert_eq!(ptree.shortest(), None);
+    }
+
+    #[test]
+    fn dist_i64_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist<i64>> = PlacementsTree::new(2, 2, 0, Dist(0));
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as i64);
+
+        assert_eq!(*ptree.shortest().unwrap(), Dist(1 + 6 + 8));
+        assert_eq!(*ptree.update_vertex(1, 1).unwrap(), Dist(1 + 6 + 8 + 1));
+        assert_eq!(ptree.update_edge(1, 2, i64::MAX), None);
+        assert_eq!(
+            ptree.shortest_path(),
+            Some((vec![0, 2, 1, 0], &Dist(2 + 9 + 4 + 1)))
+        );
+    }
+
+    #[test]
+    fn integer_test() {
+        let mut ptree: PlacementsTree<i32, i32, i32> = PlacementsTree::new(3, 2, 0, 0);
+        assert_eq!(*ptree.shortest().unwrap(), 0);
+
+        assert_eq!(*ptree.update_edge(0, 1, 2).unwrap(), 2);
+        assert_eq!(*ptree.update_vertex(1, 1).unwrap(), 1);
+        assert_eq!(ptree.update_edge(1, 2, i32::MAX), None);
+        assert_eq!(*ptree.shortest().unwrap(), 0);
+    }
+
+    #[test]
+    fn overflow_test() {
+        let mut ptree: PlacementsTree<i64, i64, i64> = PlacementsTree::new(2, 2, 0, 0);
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as i64);
+
+        assert_eq!(*ptree.update_vertex(1, -10).unwrap(), 1 + 6 + 8 - 10);
+        assert_eq!(ptree.update_edge(0, 1, i64::MIN), None);
+        assert_eq!(
+            ptree.shortest_path(),
+            Some((vec![0, 2, 1, 0], &(2 + 9 - 10 + 4)))
+        );
+        assert_eq!(
+            *ptree.update_edge(0, 1, i64::MIN + 4).unwrap(),
+            i64::MIN + 8
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use crate::apply::Apply;
+use crate::constraints::Constraints;
+use crate::graph::Graph;
+use crate::node::{dominate, least};
+use crate::recalc::RecalcAt;
+use crate::stats::Stats;
+use crate::tree::Tree;
+use crate::Max;
+
+pub struct PlacementsForest<V, E, D> {
+    graph: Graph<V, E>,
+    trees: Vec<Tree<D>>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Shortest<'a, D> {
+    pub depots: Vec<Option<&'a D>>,
+    pub overall: Option<(usize, &'a D)>,
+}
+
+impl<V, E, D> PlacementsForest<V, E, D> {
+    pub fn new<I>(n: usize, k: usize, roots: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, D)>,
+        V: Default + Clone,
+        E: Default + Clone,
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        let graph = Graph::new(n);
+        let trees = roots
+            .into_iter()
+            .map(|(key, val)| Tree::new(k, None, key, Some(key), Constraints::new(n), val, &graph))
+            .collect();
+        Self { graph, trees }
+    }
+
+    pub fn n(&self) -> usize {
+        self.graph.n
+    }
+
+    pub fn keys(&self) -> Vec<usize> {
+        self.trees.iter().map(|tree| tree.key()).collect()
+    }
+
+    pub fn root_value(&self, depot: usize) -> &D {
+        assert!(depot < self.trees.len());
+        self.trees[depot].root_value()
+    }
+
+    pub fn vertex(&self, v: usize) -> &V {
+        self.graph.vertex(v)
+    }
+
+    pub fn vertices(&self) -> &[V] {
+        &self.graph.vertices
+    }
+
+    pub fn edge(&self, v: usize, u: usize) -> &E {
+        self.graph.edge(v, u)
+    }
+
+    pub fn edges(&self) -> &[Vec<E>] {
+        &self.graph.edges
+    }
+
+    pub fn shortest(&self) -> Shortest<'_, D>
+    where
+        D: PartialOrd,
+    {
+        let mut front = Vec::new();
+        for (depot, tree) in self.trees.iter().enumerate() {
+            for (_, val) in tree.front() {
+                dominate(&mut front, (depot, val), |(_, val)| *val);
+            }
+        }
+        Shortest {
+            depots: self.trees.iter().map(|tree| tree.shortest()).collect(),
+            overall: least(front),
+        }
+    }
+
+    pub fn update_vertex<Diff>(&mut self, v: usize, diff: Diff) -> Shortest<'_, D>
+    where
+        V: Apply<Diff>,
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        self.graph.update_vertex(v, diff);
+        for tree in self.trees.iter_mut() {
+            tree.recalc_vertex(v, &self.graph, &mut Stats::default());
+        }
+        self.shortest()
+    }
+
+    pub fn update_edge<Diff>(&mut self, v: usize, u: usize, diff: Diff) -> Shortest<'_, D>
+    where
+        E: Apply<Diff>,
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        self.graph.update_edge(v, u, diff);
+        for tree in self.trees.iter_mut() {
+            tree.recalc_edge(v, u, &self.graph, &mut Stats::default());
+        }
+        self.shortest()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Dist;
+    use crate::PlacementsTree;
+
+    #[test]
+    fn update_test() {
+        let mut forest: PlacementsForest<i64, i64, Dist<i64>> =
+            PlacementsForest::new(3, 2, [(0, Dist(0)), (2, Dist(10))]);
+        let mut ptrees: [PlacementsTree<i64, i64, Dist<i64>>; 2] = [
+            PlacementsTree::new(3, 2, 0, Dist(0)),
+            PlacementsTree::new(3, 2, 2, Dist(10)),
+        ];
+        assert_eq!(forest.keys(), [0, 2]);
+        for v in 0..=3 {
+            for u in 0..=3 {
+                if v != u {
+                    let diff = (v * 4 + u) as i64;
+                    let shortest = forest.update_edge(v, u, diff);
+                    let expected = ptrees
+                        .iter_mut()
+                        .map(|ptree| {
+                            ptree.update_edge(v, u, diff);
+                            ptree.shortest().map(|dist| dist.0)
+                        })
+                        .collect::<Vec<_>>();
+                    assert_eq!(
+                        shortest
+                            .depots
+                            .iter()
+                            .map(|dist| dist.map(|dist| dist.0))
+                            .collect::<Vec<_>>(),
+                        expected
+                    );
+                }
+            }
+        }
+        let shortest = forest.shortest();
+        assert_eq!(shortest.depots, [Some(&Dist(15)), Some(&Dist(25))]);
+        assert_eq!(shortest.overall, Some((0, &Dist(15))));
+        let shortest = forest.update_vertex(1, 20);
+        assert_eq!(shortest.depots, [Some(&Dist(25)), Some(&Dist(35))]);
+        assert_eq!(shortest.overall, Some((0, &Dist(25))));
+        let shortest = forest.update_edge(0, 2, 100);
+        assert_eq!(shortest.depots, [Some(&Dist(25)), Some(&Dist(35))]);
+        assert_eq!(shortest.overall, Some((0, &Dist(25))));
+        forest.update_edge(0, 2, 2);
+        let shortest = forest.shortest();
+        assert_eq!(shortest.depots, [Some(&Dist(25)), Some(&Dist(35))]);
+        assert_eq!(shortest.overall, Some((0, &Dist(25))));
+        assert_eq!(*forest.vertex(1), 20);
+        assert_eq!(*forest.edge(3, 2), 14);
+    }
+
+    #[test]
+    fn empty_test() {
+        let mut forest: PlacementsForest<i64, i64, Dist<i64>> = PlacementsForest::new(3, 2, []);
+        let shortest = forest.update_edge(0, 1, 1);
+        assert!(shortest.depots.is_empty());
+        assert_eq!(shortest.overall, None);
+    }
+}
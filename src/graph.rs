@@ -0,0 +1,127 @@
+use crate::apply::Apply;
+
+pub struct Graph<V, E> {
+    pub vertices: Vec<V>,
+    pub edges: Vec<Vec<E>>,
+    pub disabled: Vec<bool>,
+    pub forbidden: Vec<Vec<bool>>,
+    pub symmetric: bool,
+    pub n: usize,
+}
+
+impl<V, E> Graph<V, E> {
+    pub fn new(n: usize) -> Self
+    where
+        V: Default + Clone,
+        E: Default + Clone,
+    {
+        Self {
+            vertices: vec![V::default(); n + 1],
+            edges: vec![vec![E::default(); n + 1]; n + 1],
+            disabled: vec![false; n + 1],
+            forbidden: vec![vec![false; n + 1]; n + 1],
+            symmetric: false,
+            n,
+        }
+    }
+
+    pub fn vertex(&self, v: usize) -> &V {
+        assert!(v <= self.n);
+        &self.vertices[v]
+    }
+
+    pub fn edge(&self, v: usize, u: usize) -> &E {
+        assert!(v <= self.n);
+        assert!(u <= self.n);
+        assert!(v != u);
+        &self.edges[v][u]
+    }
+
+    pub fn update_vertex<Diff>(&mut self, v: usize, diff: Diff)
+    where
+        V: Apply<Diff>,
+    {
+        assert!(v <= self.n);
+        self.vertices[v].apply(diff);
+    }
+
+    pub fn update_edge<Diff>(&mut self, v: usize, u: usize, diff: Diff)
+    where
+        E: Apply<Diff>,
+    {
+        assert!(v <= self.n);
+        assert!(u <= self.n);
+        assert!(v != u);
+        self.edges[v][u].apply(diff);
+    }
+
+    pub fn mirror_edge(&mut self, v: usize, u: usize)
+    where
+        E: Clone,
+    {
+        assert!(v <= self.n);
+        assert!(u <= self.n);
+        assert!(v != u);
+        self.edges[u][v] = self.edges[v][u].clone();
+    }
+
+    pub fn add_vertex<Out, In>(&mut self, vertex: V, outgoing: Out, incoming: In)
+    where
+        Out: IntoIterator<Item = E>,
+        In: IntoIterator<Item = E>,
+        E: Default,
+    {
+        let n = self.n + 1;
+        let mut outgoing = outgoing.into_iter().collect::<Vec<_>>();
+        let incoming = incoming.into_iter().collect::<Vec<_>>();
+        assert!(outgoing.len() == n);
+        assert!(incoming.len() == n);
+        outgoing.push(E::default());
+        for (edges, edge) in self.edges.iter_mut().zip(incoming) {
+            edges.push(edge);
+        }
+        self.edges.push(outgoing);
+        self.vertices.push(vertex);
+        self.disabled.push(false);
+        for forbidden in self.forbidden.iter_mut() {
+            forbidden.push(false);
+        }
+        self.forbidden.push(vec![false; n + 1]);
+        self.n = n;
+    }
+
+    pub fn disable(&mut self, v: usize) -> bool {
+        assert!(v <= self.n);
+        !std::mem::replace(&mut self.disabled[v], true)
+    }
+
+    pub fn enable(&mut self, v: usize) -> bool {
+        assert!(v <= self.n);
+        std::mem::replace(&mut self.disabled[v], false)
+    }
+
+    pub fn forbid(&mut self, v: usize, u: usize) -> bool {
+        assert!(v <= self.n);
+        assert!(u <= self.n);
+        assert!(v != u);
+        !std::mem::replace(&mut self.forbidden[v][u], true)
+    }
+
+    pub fn allow(&mut self, v: usize, u: usize) -> bool {
+        assert!(v <= self.n);
+        assert!(u <= self.n);
+        assert!(v != u);
+        std::mem::replace(&mut self.forbidden[v][u], false)
+    }
+
+    pub fn is_disabled(&self, v: usize) -> bool {
+        assert!(v <= self.n);
+        self.disabled[v]
+    }
+
+    pub fn is_forbidden(&self, v: usize, u: usize) -> bool {
+        assert!(v <= self.n);
+        assert!(u <= self.n);
+        self.forbidden[v][u]
+    }
+}
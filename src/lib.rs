@@ -1,24 +1,50 @@
 mod apply;
+mod builder;
+mod capacitated;
+mod constraints;
+mod diff;
+mod dist;
 mod fill;
+mod forest;
+mod graph;
 mod max;
 mod node;
+mod pareto;
 mod recalc;
+pub mod reference;
+mod stats;
+#[cfg(test)]
+mod testing;
+mod time_windowed;
+mod total;
+mod trace;
+mod tree;
 
 pub use crate::apply::Apply;
-use crate::fill::Fill;
+pub use crate::builder::Builder;
+pub use crate::capacitated::Capacitated;
+pub use crate::diff::{Add, Scale, Set, Sub, With};
+pub use crate::dist::Dist;
+pub use crate::forest::{PlacementsForest, Shortest};
+use crate::graph::Graph;
 pub use crate::max::Max;
-use crate::node::Node;
-pub use crate::recalc::Recalc;
-use std::collections::LinkedList;
+pub use crate::node::Mismatch;
+pub use crate::pareto::Pareto;
+pub use crate::recalc::{Recalc, RecalcAt, TryRecalc};
+pub use crate::stats::Stats;
+pub use crate::time_windowed::{TimeWindowed, Window};
+pub use crate::total::Total;
+use crate::tree::Tree;
 use std::ptr::NonNull;
+use std::time::Instant;
+
+type Observer<D> = Box<dyn FnMut(&Tree<D>)>;
 
 pub struct PlacementsTree<V, E, D> {
-    _root: Box<Node<D>>,
-    vertices: Vec<V>,
-    vertices_idx: Vec<LinkedList<NonNull<Node<D>>>>,
-    edges: Vec<Vec<E>>,
-    edges_idx: Vec<Vec<LinkedList<NonNull<Node<D>>>>>,
-    n: usize,
+    graph: Graph<V, E>,
+    tree: Tree<D>,
+    observers: Vec<Observer<D>>,
+    stats: Stats,
 }
 
 impl<V, E, D> PlacementsTree<V, E, D> {
@@ -26,89 +52,362 @@ impl<V, E, D> PlacementsTree<V, E, D> {
     where
         V: Default + Clone,
         E: Default + Clone,
-        D: Max,
-    {
-        assert!(key <= n);
-        let k = k.min(n);
-        let root = Node::root(n, k, key, val);
-        let vertices = vec![V::default(); n + 1];
-        let mut vertices_idx = vec![LinkedList::new(); n + 1];
-        root.fill(&mut vertices_idx);
-        let edges = vec![vec![E::default(); n + 1]; n + 1];
-        let mut edges_idx = vec![vec![LinkedList::new(); n + 1]; n + 1];
-        root.fill(&mut edges_idx);
-        Self {
-            _root: root,
-            vertices,
-            vertices_idx,
-            edges,
-            edges_idx,
-            n,
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        Builder::new(n, k, key).build(val)
+    }
+
+    pub fn n(&self) -> usize {
+        self.graph.n
+    }
+
+    pub fn k(&self) -> usize {
+        self.tree.k()
+    }
+
+    pub fn min_k(&self) -> usize {
+        self.tree.min_k()
+    }
+
+    pub fn key(&self) -> usize {
+        self.tree.key()
+    }
+
+    pub fn end(&self) -> Option<usize> {
+        self.tree.end()
+    }
+
+    pub fn is_symmetric(&self) -> bool {
+        self.graph.symmetric
+    }
+
+    pub fn root_value(&self) -> &D {
+        self.tree.root_value()
+    }
+
+    pub fn vertex(&self, v: usize) -> &V {
+        self.graph.vertex(v)
+    }
+
+    pub fn vertices(&self) -> &[V] {
+        &self.graph.vertices
+    }
+
+    pub fn edge(&self, v: usize, u: usize) -> &E {
+        self.graph.edge(v, u)
+    }
+
+    pub fn edges(&self) -> &[Vec<E>] {
+        &self.graph.edges
+    }
+
+    pub fn add_vertex<Out, In>(&mut self, vertex: V, outgoing: Out, incoming: In) -> Option<&D>
+    where
+        Out: IntoIterator<Item = E>,
+        In: IntoIterator<Item = E>,
+        E: Default,
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        assert!(!self.graph.symmetric);
+        let now = self.reset_stats();
+        self.graph.add_vertex(vertex, outgoing, incoming);
+        self.insert(now)
+    }
+
+    pub fn add_symmetric_vertex<I>(&mut self, vertex: V, edges: I) -> Option<&D>
+    where
+        I: IntoIterator<Item = E>,
+        E: Default + Clone,
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        assert!(self.graph.symmetric);
+        let now = self.reset_stats();
+        let edges = edges.into_iter().collect::<Vec<_>>();
+        self.graph.add_vertex(vertex, edges.clone(), edges);
+        self.insert(now)
+    }
+
+    pub fn set_k(&mut self, k: usize) -> Option<&D>
+    where
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        let now = self.reset_stats();
+        let shortest = self
+            .tree
+            .set_k(k, &self.graph, &mut self.stats)
+            .map(NonNull::from);
+        self.stats.elapsed = now.elapsed();
+        self.notify(shortest)
+    }
+
+    pub fn shortest(&self) -> Option<&D>
+    where
+        D: PartialOrd,
+    {
+        self.tree.shortest()
+    }
+
+    pub fn shortest_path(&self) -> Option<(Vec<usize>, &D)>
+    where
+        D: PartialOrd,
+    {
+        self.tree.shortest_path()
+    }
+
+    pub fn shortest_len(&self, len: usize) -> Option<&D>
+    where
+        D: PartialOrd,
+    {
+        self.tree.shortest_len(len)
+    }
+
+    pub fn front(&self) -> Vec<(Vec<usize>, &D)>
+    where
+        D: PartialOrd,
+    {
+        self.tree.front()
+    }
+
+    pub fn validate(&self) -> Vec<Mismatch<'_, D>>
+    where
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        self.tree.validate(&self.graph)
+    }
+
+    pub fn disable_vertex(&mut self, v: usize)
+    where
+        D: PartialOrd,
+    {
+        assert!(v != self.tree.key());
+        assert!(Some(v) != self.tree.end());
+        if self.graph.disable(v) {
+            self.tree.mask_vertex(v);
+            self.notify(None);
+        }
+    }
+
+    pub fn enable_vertex(&mut self, v: usize)
+    where
+        D: PartialOrd,
+    {
+        assert!(v != self.tree.key());
+        assert!(Some(v) != self.tree.end());
+        if self.graph.enable(v) {
+            self.tree.unmask_vertex(v);
+            self.notify(None);
+        }
+    }
+
+    pub fn is_disabled(&self, v: usize) -> bool {
+        self.graph.is_disabled(v)
+    }
+
+    pub fn forbid_edge(&mut self, v: usize, u: usize)
+    where
+        D: PartialOrd,
+    {
+        let mut changed = false;
+        for (v, u) in self.directions(v, u) {
+            if self.graph.forbid(v, u) {
+                self.tree.mask_edge(v, u);
+                changed = true;
+            }
+        }
+        if changed {
+            self.notify(None);
+        }
+    }
+
+    pub fn allow_edge(&mut self, v: usize, u: usize)
+    where
+        D: PartialOrd,
+    {
+        let mut changed = false;
+        for (v, u) in self.directions(v, u) {
+            if self.graph.allow(v, u) {
+                self.tree.unmask_edge(v, u);
+                changed = true;
+            }
+        }
+        if changed {
+            self.notify(None);
         }
     }
 
+    fn directions(&self, v: usize, u: usize) -> Vec<(usize, usize)> {
+        if self.graph.symmetric {
+            vec![(v, u), (u, v)]
+        } else {
+            vec![(v, u)]
+        }
+    }
+
+    pub fn is_forbidden(&self, v: usize, u: usize) -> bool {
+        self.graph.is_forbidden(v, u)
+    }
+
     pub fn update_vertex<Diff>(&mut self, v: usize, diff: Diff) -> Option<&D>
     where
         V: Apply<Diff>,
-        D: Recalc<V, E> + PartialOrd,
+        D: RecalcAt<V, E> + PartialOrd + Max,
     {
-        assert!(v <= self.n);
-        self.vertices[v].apply(diff);
-        if self.vertices_idx[v].is_empty() {
-            None
+        let span = trace::span!("update_vertex", v = v; seeded, recalced, leaves);
+        let now = self.reset_stats();
+        self.graph.update_vertex(v, diff);
+        let shortest = self
+            .tree
+            .recalc_vertex(v, &self.graph, &mut self.stats)
+            .map(NonNull::from);
+        self.stats.elapsed = now.elapsed();
+        self.record(&span);
+        self.notify(shortest)
+    }
+
+    pub fn update_edge<Diff>(&mut self, v: usize, u: usize, diff: Diff) -> Option<&D>
+    where
+        E: Apply<Diff> + Clone,
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        let span = trace::span!("update_edge", v = v, u = u; seeded, recalced, leaves);
+        let now = self.reset_stats();
+        self.graph.update_edge(v, u, diff);
+        let shortest = if self.graph.symmetric {
+            self.graph.mirror_edge(v, u);
+            self.tree
+                .recalc_edges(&[(v, u), (u, v)], &self.graph, &mut self.stats)
         } else {
-            unsafe {
-                let mut vertices = self.vertices_idx[v].iter_mut();
-                let mut shortest = vertices
-                    .next()
-                    .map(|vertex| vertex.as_mut().recalc_children(&self.vertices, &self.edges))
-                    .unwrap();
-                for vertex in vertices {
-                    let recalced = vertex.as_mut().recalc_children(&self.vertices, &self.edges);
-                    if recalced < shortest {
-                        shortest = recalced;
-                    }
-                }
-                Some(shortest)
+            self.tree.recalc_edge(v, u, &self.graph, &mut self.stats)
+        };
+        let shortest = shortest.map(NonNull::from);
+        self.stats.elapsed = now.elapsed();
+        self.record(&span);
+        self.notify(shortest)
+    }
+
+    pub fn update_outgoing<I, Diff>(&mut self, v: usize, diffs: I) -> Option<&D>
+    where
+        I: IntoIterator<Item = (usize, Diff)>,
+        E: Apply<Diff> + Clone,
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        let now = self.reset_stats();
+        let mut edges = Vec::new();
+        for (u, diff) in diffs {
+            self.graph.update_edge(v, u, diff);
+            edges.push((v, u));
+        }
+        self.recalc_edges(edges, now)
+    }
+
+    pub fn update_incoming<I, Diff>(&mut self, u: usize, diffs: I) -> Option<&D>
+    where
+        I: IntoIterator<Item = (usize, Diff)>,
+        E: Apply<Diff> + Clone,
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        let now = self.reset_stats();
+        let mut edges = Vec::new();
+        for (v, diff) in diffs {
+            self.graph.update_edge(v, u, diff);
+            edges.push((v, u));
+        }
+        self.recalc_edges(edges, now)
+    }
+
+    fn recalc_edges(&mut self, mut edges: Vec<(usize, usize)>, now: Instant) -> Option<&D>
+    where
+        E: Clone,
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        if self.graph.symmetric {
+            for i in 0..edges.len() {
+                let (v, u) = edges[i];
+                self.graph.mirror_edge(v, u);
+                edges.push((u, v));
             }
         }
+        edges.sort_unstable();
+        edges.dedup();
+        let shortest = self
+            .tree
+            .recalc_edges(&edges, &self.graph, &mut self.stats)
+            .map(NonNull::from);
+        self.stats.elapsed = now.elapsed();
+        self.notify(shortest)
     }
 
-    pub fn update_edge<Diff>(&mut self, v: usize, u: usize, diff: Diff) -> Option<&D>
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    fn record(&self, span: &trace::Span) {
+        span.record("seeded", self.stats.seeded)
+            .record("recalced", self.stats.recalced)
+            .record("leaves", self.stats.leaves);
+    }
+
+    fn insert(&mut self, now: Instant) -> Option<&D>
     where
-        E: Apply<Diff>,
-        D: Recalc<V, E> + PartialOrd,
+        D: RecalcAt<V, E> + PartialOrd + Max,
     {
-        assert!(v <= self.n);
-        assert!(u <= self.n);
-        assert!(v != u);
-        self.edges[v][u].apply(diff);
-        if self.edges_idx[v][u].is_empty() {
-            None
-        } else {
-            unsafe {
-                let mut edges = self.edges_idx[v][u].iter_mut();
-                let mut shortest = edges
-                    .next()
-                    .map(|edge| edge.as_mut().recalc(&self.vertices, &self.edges))
-                    .unwrap();
-                for edge in edges {
-                    let recalced = edge.as_mut().recalc(&self.vertices, &self.edges);
-                    if recalced < shortest {
-                        shortest = recalced;
-                    }
+        let shortest = self
+            .tree
+            .insert(&self.graph, &mut self.stats)
+            .map(NonNull::from);
+        self.stats.elapsed = now.elapsed();
+        self.notify(shortest)
+    }
+
+    fn reset_stats(&mut self) -> Instant {
+        self.stats = Stats::default();
+        Instant::now()
+    }
+
+    pub fn subscribe<F>(&mut self, mut listener: F)
+    where
+        F: FnMut(Option<(&[usize], &D)>, Option<(&[usize], &D)>) + 'static,
+        D: PartialOrd + Clone + 'static,
+    {
+        let mut best = self
+            .tree
+            .shortest_path()
+            .map(|(path, val)| (path, val.clone()));
+        self.observers.push(Box::new(move |tree| {
+            let shortest = tree.shortest_path();
+            let changed = match (&best, &shortest) {
+                (Some((path, val)), Some((shortest_path, shortest))) => {
+                    path != shortest_path || val != *shortest
                 }
-                Some(shortest)
+                (None, None) => false,
+                _ => true,
+            };
+            if changed {
+                listener(
+                    best.as_ref().map(|(path, val)| (path.as_slice(), val)),
+                    shortest.as_ref().map(|(path, val)| (path.as_slice(), *val)),
+                );
+                best = shortest.map(|(path, val)| (path, val.clone()));
             }
+        }));
+    }
+
+    fn notify(&mut self, shortest: Option<NonNull<D>>) -> Option<&D> {
+        for observer in self.observers.iter_mut() {
+            observer(&self.tree);
         }
+        shortest.map(|shortest| unsafe { shortest.as_ref() })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::update_edges;
+    use std::cell::RefCell;
+    use std::rc::Rc;
 
-    #[derive(PartialEq, Eq, PartialOrd, Debug)]
+    #[derive(Clone, PartialEq, Eq, PartialOrd, Debug)]
     struct Dist(i64);
 
     impl Max for Dist {
@@ -127,6 +426,42 @@ mod tests {
         }
     }
 
+    #[derive(PartialEq, Eq, PartialOrd, Debug)]
+    struct Staged(i64);
+
+    impl Max for Staged {
+        fn max() -> Self {
+            Staged(i64::MAX)
+        }
+    }
+
+    impl RecalcAt<i64, i64> for Staged {
+        fn recalc_at(
+            &self,
+            depth: usize,
+            _: usize,
+            _: usize,
+            vertex: &i64,
+            edge: &i64,
+            _: &i64,
+        ) -> Self {
+            if *self == Self::max() {
+                Self::max()
+            } else {
+                Self(self.0 + vertex + edge * depth as i64)
+            }
+        }
+    }
+
+    #[derive(PartialEq, Eq, PartialOrd, Debug)]
+    struct Bounded(i64);
+
+    impl TryRecalc<i64, i64> for Bounded {
+        fn try_recalc(&self, vertex: &i64, edge: &i64) -> Option<Self> {
+            Some(Self(self.0 + vertex + edge)).filter(|bounded| bounded.0 <= 20)
+        }
+    }
+
     #[test]
     #[should_panic(expected = "assertion failed: key <= n")]
     fn new_panicked_test() {
@@ -136,8 +471,8 @@ mod tests {
     #[test]
     fn update_test() {
         let mut ptree: PlacementsTree<i64, i64, Dist> = PlacementsTree::new(2, 2, 0, Dist(0));
-        assert_eq!(*ptree.update_vertex(1, 1).unwrap(), Dist::max());
-        assert_eq!(*ptree.update_vertex(2, 1).unwrap(), Dist::max());
+        assert_eq!(*ptree.update_vertex(1, 1).unwrap(), Dist(1));
+        assert_eq!(*ptree.update_vertex(2, 1).unwrap(), Dist(2));
         assert_eq!(*ptree.update_edge(0, 1, 1).unwrap(), Dist(3));
         assert_eq!(*ptree.update_edge(0, 2, 2).unwrap(), Dist(4));
         assert_eq!(*ptree.update_edge(1, 0, 3).unwrap(), Dist(7));
@@ -147,6 +482,451 @@ mod tests {
         assert_eq!(*ptree.update_vertex(0, 1).unwrap(), Dist(13));
     }
 
+    #[test]
+    fn add_vertex_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> = PlacementsTree::new(2, 2, 0, Dist(0));
+        ptree.update_edge(0, 1, 1);
+        ptree.update_edge(0, 2, 2);
+        ptree.update_edge(1, 0, 3);
+        ptree.update_edge(1, 2, 4);
+        ptree.update_edge(2, 0, 5);
+        ptree.update_edge(2, 1, 6);
+        assert_eq!(
+            *ptree.add_vertex(0, [7, 8, 9], [10, 11, 12]).unwrap(),
+            Dist(19)
+        );
+        assert_eq!(ptree.n(), 3);
+        assert_eq!(*ptree.edge(3, 1), 8);
+        assert_eq!(*ptree.edge(2, 3), 12);
+        assert_eq!(*ptree.update_edge(1, 3, 20).unwrap(), Dist(28));
+        assert_eq!(*ptree.update_edge(3, 0, 0).unwrap(), Dist(14));
+        assert_eq!(*ptree.update_edge(1, 2, 0).unwrap(), Dist(6));
+        assert_eq!(*ptree.update_vertex(3, 1).unwrap(), Dist(15));
+    }
+
+    #[test]
+    fn add_vertex_empty_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> = PlacementsTree::new(2, 0, 0, Dist(0));
+        assert_eq!(ptree.add_vertex(0, [0, 0, 0], [0, 0, 0]), None);
+        assert_eq!(*ptree.update_vertex(0, 1).unwrap(), Dist(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: outgoing.len() == n")]
+    fn add_vertex_panicked_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> = PlacementsTree::new(2, 2, 0, Dist(0));
+        ptree.add_vertex(0, [0, 0], [0, 0, 0]);
+    }
+
+    #[test]
+    fn disable_vertex_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> = PlacementsTree::new(3, 1, 0, Dist(0));
+        ptree.update_edge(0, 1, 1);
+        ptree.update_edge(0, 2, 2);
+        ptree.update_edge(0, 3, 3);
+        assert_eq!(*ptree.update_vertex(0, 0).unwrap(), Dist(1));
+        ptree.disable_vertex(1);
+        ptree.disable_vertex(1);
+        assert!(ptree.is_disabled(1));
+        assert_eq!(*ptree.update_vertex(0, 0).unwrap(), Dist(2));
+        assert_eq!(ptree.update_edge(0, 1, 0), None);
+        assert_eq!(ptree.update_vertex(1, 0), None);
+        ptree.enable_vertex(1);
+        assert!(!ptree.is_disabled(1));
+        assert_eq!(*ptree.update_vertex(0, 0).unwrap(), Dist(0));
+        ptree.disable_vertex(2);
+        ptree.disable_vertex(3);
+        ptree.add_vertex(0, [4, 0, 0, 0], [4, 0, 0, 0]);
+        assert_eq!(*ptree.update_vertex(0, 0).unwrap(), Dist(0));
+        ptree.disable_vertex(1);
+        assert_eq!(*ptree.update_vertex(0, 0).unwrap(), Dist(8));
+        ptree.disable_vertex(4);
+        assert_eq!(ptree.update_vertex(0, 0), None);
+    }
+
+    #[test]
+    fn disable_vertex_deep_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> = PlacementsTree::new(3, 2, 0, Dist(0));
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as i64);
+        assert_eq!(*ptree.update_vertex(0, 0).unwrap(), Dist(1 + 6 + 8));
+        ptree.disable_vertex(2);
+        assert_eq!(*ptree.update_vertex(0, 0).unwrap(), Dist(1 + 7 + 12));
+        ptree.disable_vertex(3);
+        assert_eq!(ptree.update_vertex(0, 0), None);
+        ptree.enable_vertex(2);
+        assert_eq!(*ptree.update_edge(2, 1, 0).unwrap(), Dist(2 + 4));
+        assert_eq!(*ptree.update_vertex(0, 0).unwrap(), Dist(2 + 4));
+        ptree.disable_vertex(1);
+        assert_eq!(*ptree.add_vertex(0, [0; 4], [0; 4]).unwrap(), Dist(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: v != self.tree.key()")]
+    fn disable_vertex_panicked_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> = PlacementsTree::new(2, 2, 0, Dist(0));
+        ptree.disable_vertex(0);
+    }
+
+    #[test]
+    fn forbid_edge_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> = PlacementsTree::new(3, 2, 0, Dist(0));
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as i64);
+        ptree.forbid_edge(1, 2);
+        ptree.forbid_edge(1, 2);
+        assert!(ptree.is_forbidden(1, 2));
+        assert!(!ptree.is_forbidden(2, 1));
+        assert_eq!(*ptree.update_vertex(0, 0).unwrap(), Dist(2 + 9 + 4));
+        assert_eq!(ptree.update_edge(1, 2, 0), None);
+        ptree.forbid_edge(1, 0);
+        assert_eq!(*ptree.update_vertex(0, 0).unwrap(), Dist(1 + 7 + 12));
+        ptree.disable_vertex(3);
+        ptree.forbid_edge(3, 0);
+        assert_eq!(ptree.update_vertex(0, 0), None);
+        ptree.allow_edge(1, 0);
+        assert_eq!(*ptree.update_vertex(0, 0).unwrap(), Dist(2 + 9 + 4));
+        ptree.enable_vertex(3);
+        assert_eq!(*ptree.update_vertex(0, 0).unwrap(), Dist(2 + 9 + 4));
+        ptree.allow_edge(1, 2);
+        assert!(!ptree.is_forbidden(1, 2));
+        assert_eq!(*ptree.update_vertex(0, 0).unwrap(), Dist(1 + 8));
+        ptree.forbid_edge(2, 0);
+        assert_eq!(*ptree.add_vertex(0, [0; 4], [0; 4]).unwrap(), Dist(1));
+        assert_eq!(*ptree.update_edge(2, 4, 0).unwrap(), Dist(2));
+        assert_eq!(ptree.update_edge(4, 2, 0), None);
+    }
+
+    #[test]
+    fn set_k_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> = PlacementsTree::new(3, 1, 0, Dist(0));
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as i64);
+        assert_eq!(*ptree.shortest().unwrap(), Dist(1 + 4));
+        ptree.forbid_edge(1, 0);
+        assert_eq!(*ptree.shortest().unwrap(), Dist(2 + 8));
+        assert_eq!(*ptree.set_k(2).unwrap(), Dist(1 + 6 + 8));
+        assert_eq!(ptree.k(), 2);
+        assert_eq!(*ptree.set_k(5).unwrap(), Dist(1 + 6 + 11 + 12));
+        assert_eq!(ptree.k(), 3);
+        assert_eq!(*ptree.update_edge(3, 0, 0).unwrap(), Dist(1 + 6 + 11));
+        assert_eq!(*ptree.set_k(1).unwrap(), Dist(3));
+        assert_eq!(*ptree.update_edge(0, 3, 9).unwrap(), Dist(9));
+        assert_eq!(*ptree.shortest().unwrap(), Dist(9));
+        assert_eq!(*ptree.set_k(0).unwrap(), Dist(0));
+        assert_eq!(*ptree.update_vertex(0, 1).unwrap(), Dist(1));
+    }
+
+    #[test]
+    fn open_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> = Builder::new(2, 2, 0).open().build(Dist(0));
+        assert_eq!(ptree.end(), None);
+        assert_eq!(*ptree.update_edge(0, 1, 1).unwrap(), Dist(1));
+        assert_eq!(*ptree.update_edge(1, 2, 4).unwrap(), Dist(5));
+        assert_eq!(*ptree.update_edge(0, 2, 2).unwrap(), Dist(2));
+        assert_eq!(*ptree.update_edge(2, 1, 6).unwrap(), Dist(8));
+        assert_eq!(ptree.update_edge(1, 0, 3), None);
+        assert_eq!(ptree.update_edge(2, 0, 5), None);
+        assert_eq!(*ptree.update_vertex(2, 1).unwrap(), Dist(5));
+        assert_eq!(*ptree.shortest().unwrap(), Dist(5));
+        assert_eq!(*ptree.add_vertex(0, [0; 3], [0, 0, 1]).unwrap(), Dist(0));
+        assert_eq!(ptree.update_edge(3, 0, 1), None);
+        assert_eq!(*ptree.set_k(1).unwrap(), Dist(0));
+        assert_eq!(*ptree.update_edge(0, 3, 3).unwrap(), Dist(3));
+        assert_eq!(*ptree.set_k(3).unwrap(), Dist(1));
+        assert_eq!(*ptree.set_k(0).unwrap(), Dist(0));
+    }
+
+    #[test]
+    fn end_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> = Builder::new(3, 3, 0).end(3).build(Dist(0));
+        assert_eq!(ptree.k(), 2);
+        assert_eq!(ptree.end(), Some(3));
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as i64);
+        assert_eq!(*ptree.shortest().unwrap(), Dist(1 + 6 + 11));
+        assert_eq!(ptree.update_edge(3, 1, 0), None);
+        assert_eq!(*ptree.update_vertex(3, 5).unwrap(), Dist(1 + 6 + 11));
+        assert_eq!(*ptree.update_vertex(2, 5).unwrap(), Dist(1 + 6 + 5 + 11));
+        assert_eq!(*ptree.set_k(1).unwrap(), Dist(1 + 7));
+        assert_eq!(*ptree.add_vertex(0, [0; 4], [9, 0, 0, 0]).unwrap(), Dist(9));
+        assert_eq!(*ptree.update_edge(4, 3, 0).unwrap(), Dist(9));
+        assert_eq!(*ptree.set_k(5).unwrap(), Dist(1 + 6 + 5));
+        assert_eq!(ptree.k(), 3);
+    }
+
+    #[test]
+    fn min_k_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> =
+            Builder::new(3, 3, 0).min_k(1).build(Dist(0));
+        assert_eq!(ptree.min_k(), 1);
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as i64);
+        assert_eq!(*ptree.shortest().unwrap(), Dist(1 + 4));
+        assert_eq!(ptree.shortest_len(0), None);
+        assert_eq!(*ptree.shortest_len(1).unwrap(), Dist(1 + 4));
+        assert_eq!(*ptree.shortest_len(2).unwrap(), Dist(1 + 6 + 8));
+        assert_eq!(*ptree.shortest_len(3).unwrap(), Dist(1 + 6 + 11 + 12));
+        assert_eq!(ptree.shortest_len(4), None);
+        ptree.forbid_edge(1, 0);
+        assert_eq!(*ptree.shortest().unwrap(), Dist(2 + 8));
+        assert_eq!(*ptree.set_k(2).unwrap(), Dist(2 + 8));
+        assert_eq!(ptree.shortest_len(3), None);
+    }
+
+    #[test]
+    fn min_k_open_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> =
+            Builder::new(3, 1, 0).min_k(2).open().build(Dist(0));
+        assert_eq!(ptree.min_k(), 1);
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as i64);
+        assert_eq!(*ptree.shortest().unwrap(), Dist(1));
+        assert_eq!(*ptree.set_k(3).unwrap(), Dist(1 + 6));
+        assert_eq!(ptree.min_k(), 2);
+        assert_eq!(ptree.shortest_len(1), None);
+        assert_eq!(*ptree.shortest_len(2).unwrap(), Dist(1 + 6));
+        assert_eq!(*ptree.shortest_len(3).unwrap(), Dist(1 + 6 + 11));
+        assert_eq!(*ptree.update_edge(0, 1, 100).unwrap(), Dist(100 + 6));
+        assert_eq!(*ptree.shortest().unwrap(), Dist(2 + 9));
+    }
+
+    #[test]
+    fn recalc_at_test() {
+        let mut ptree: PlacementsTree<i64, i64, Staged> = PlacementsTree::new(2, 2, 0, Staged(0));
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as i64);
+        assert_eq!(*ptree.shortest().unwrap(), Staged(2 + 9 * 2 + 4 * 3));
+        assert_eq!(*ptree.update_edge(1, 0, 0).unwrap(), Staged(2 + 9 * 2));
+        assert_eq!(*ptree.update_vertex(2, 5).unwrap(), Staged(2 + 5 + 9 * 2));
+        assert_eq!(*ptree.shortest().unwrap(), Staged(2 + 5 + 9 * 2));
+    }
+
+    #[test]
+    fn build_test() {
+        let mut ptree: PlacementsTree<i64, i64, Option<Bounded>> =
+            PlacementsTree::new(2, 2, 0, Some(Bounded(0)));
+        assert_eq!(*ptree.shortest().unwrap(), Some(Bounded(0)));
+        assert_eq!(*ptree.update_edge(0, 1, 1).unwrap(), Some(Bounded(1)));
+        assert_eq!(
+            ptree.shortest_path(),
+            Some((vec![0, 2, 1, 0], &Some(Bounded(0))))
+        );
+        assert_eq!(ptree.validate(), vec![]);
+    }
+
+    #[test]
+    fn try_recalc_test() {
+        let mut ptree: PlacementsTree<i64, i64, Option<Bounded>> =
+            PlacementsTree::new(2, 2, 0, Some(Bounded(0)));
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as i64);
+        assert_eq!(*ptree.shortest().unwrap(), Some(Bounded(1 + 6 + 8)));
+        assert_eq!(
+            *ptree.update_edge(1, 2, 10).unwrap(),
+            Some(Bounded(1 + 10 + 8))
+        );
+        assert_eq!(ptree.update_edge(2, 0, 10), None);
+        assert_eq!(*ptree.shortest().unwrap(), Some(Bounded(2 + 9 + 4)));
+        assert_eq!(ptree.update_vertex(0, 20), None);
+        assert_eq!(ptree.shortest(), None);
+        assert_eq!(
+            *ptree.update_vertex(0, 0).unwrap(),
+            Some(Bounded(2 + 9 + 4))
+        );
+    }
+
+    #[test]
+    fn precedence_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> =
+            Builder::new(3, 2, 0).precedence([(2, 1)]).build(Dist(0));
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as i64);
+        assert_eq!(*ptree.shortest().unwrap(), Dist(2 + 9 + 4));
+        assert_eq!(ptree.update_edge(1, 2, 0), None);
+        assert_eq!(*ptree.update_edge(2, 1, 100).unwrap(), Dist(2 + 100 + 4));
+        assert_eq!(*ptree.shortest().unwrap(), Dist(2 + 11 + 12));
+        assert_eq!(*ptree.add_vertex(0, [0; 4], [0; 4]).unwrap(), Dist(2));
+        assert_eq!(ptree.update_edge(4, 1, 0), None);
+        assert_eq!(*ptree.set_k(3).unwrap(), Dist(2 + 4));
+    }
+
+    #[test]
+    fn required_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> =
+            Builder::new(3, 3, 0).min_k(1).required([3]).build(Dist(0));
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as i64);
+        assert_eq!(*ptree.shortest().unwrap(), Dist(3 + 12));
+        assert_eq!(*ptree.shortest_len(2).unwrap(), Dist(1 + 7 + 12));
+        assert_eq!(*ptree.update_edge(1, 2, 0).unwrap(), Dist(1 + 11 + 12));
+        assert_eq!(*ptree.update_edge(0, 3, 100).unwrap(), Dist(100 + 12));
+        assert_eq!(*ptree.shortest().unwrap(), Dist(1 + 7 + 12));
+        assert_eq!(*ptree.set_k(1).unwrap(), Dist(100 + 12));
+        assert_eq!(*ptree.set_k(3).unwrap(), Dist(1 + 7 + 12));
+        assert_eq!(*ptree.add_vertex(0, [0; 4], [0; 4]).unwrap(), Dist(1 + 7));
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: j != self.key")]
+    fn precedence_panicked_test() {
+        let _: PlacementsTree<i64, i64, Dist> =
+            Builder::new(3, 2, 0).precedence([(1, 0)]).build(Dist(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: Some(i) != self.end || i == self.key")]
+    fn precedence_ended_panicked_test() {
+        let _: PlacementsTree<i64, i64, Dist> = Builder::new(3, 2, 0)
+            .end(3)
+            .precedence([(3, 1)])
+            .build(Dist(0));
+    }
+
+    #[test]
+    fn symmetric_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> =
+            Builder::new(2, 1, 0).symmetric().build(Dist(0));
+        assert!(ptree.is_symmetric());
+        assert_eq!(*ptree.update_edge(0, 1, 5).unwrap(), Dist(5 + 5));
+        assert_eq!(*ptree.edge(1, 0), 5);
+        assert_eq!(*ptree.update_edge(2, 0, 3).unwrap(), Dist(3 + 3));
+        assert_eq!(*ptree.edge(0, 2), 3);
+        assert_eq!(*ptree.update_edge(1, 0, Add(1)).unwrap(), Dist(6 + 6));
+        assert_eq!(*ptree.shortest().unwrap(), Dist(3 + 3));
+        assert_eq!(*ptree.set_k(2).unwrap(), Dist(6 + 3));
+        assert_eq!(*ptree.update_edge(2, 1, 1).unwrap(), Dist(6 + 1 + 3));
+        assert_eq!(*ptree.edge(1, 2), 1);
+        assert_eq!(*ptree.shortest().unwrap(), Dist(6 + 1 + 3));
+        ptree.forbid_edge(2, 1);
+        assert!(ptree.is_forbidden(1, 2));
+        assert_eq!(ptree.shortest(), None);
+        ptree.allow_edge(1, 2);
+        assert!(!ptree.is_forbidden(2, 1));
+        assert_eq!(*ptree.shortest().unwrap(), Dist(6 + 1 + 3));
+        ptree.add_symmetric_vertex(0, [1, 2, 3]);
+        assert_eq!(*ptree.edge(0, 3), 1);
+        assert_eq!(*ptree.edge(3, 0), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: !self.graph.symmetric")]
+    fn add_vertex_symmetric_panicked_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> =
+            Builder::new(2, 2, 0).symmetric().build(Dist(0));
+        ptree.add_vertex(0, [1, 2, 3], [1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: self.graph.symmetric")]
+    fn add_symmetric_vertex_panicked_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> = PlacementsTree::new(2, 2, 0, Dist(0));
+        ptree.add_symmetric_vertex(0, [1, 2, 3]);
+    }
+
+    #[test]
+    fn update_outgoing_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> = PlacementsTree::new(3, 2, 0, Dist(0));
+        for v in 0..=3 {
+            ptree.update_outgoing(
+                v,
+                (0..=3).filter(|&u| u != v).map(|u| (u, (v * 4 + u) as i64)),
+            );
+        }
+        assert_eq!(*ptree.shortest().unwrap(), Dist(1 + 6 + 8));
+        assert_eq!(
+            *ptree.update_outgoing(0, [(1, 9), (3, 0)]).unwrap(),
+            Dist(13 + 4)
+        );
+        assert_eq!(*ptree.edge(0, 1), 9);
+        assert_eq!(*ptree.edge(0, 2), 2);
+        assert_eq!(
+            *ptree.update_incoming(0, [(2, 0), (3, 20)]).unwrap(),
+            Dist(14)
+        );
+        assert_eq!(*ptree.edge(3, 0), 20);
+        assert_eq!(*ptree.shortest().unwrap(), Dist(14));
+        assert_eq!(ptree.update_outgoing(1, Vec::<(usize, i64)>::new()), None);
+    }
+
+    #[test]
+    fn update_outgoing_symmetric_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> =
+            Builder::new(2, 2, 0).symmetric().build(Dist(0));
+        assert_eq!(
+            *ptree.update_outgoing(0, [(1, 1), (2, 2)]).unwrap(),
+            Dist(1 + 2)
+        );
+        assert_eq!(*ptree.edge(2, 0), 2);
+        assert_eq!(
+            *ptree.update_incoming(2, [(1, 5)]).unwrap(),
+            Dist(1 + 5 + 2)
+        );
+        assert_eq!(*ptree.edge(2, 1), 5);
+    }
+
+    #[test]
+    fn subscribe_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> = PlacementsTree::new(2, 2, 0, Dist(0));
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let listener = changes.clone();
+        ptree.subscribe(move |old, new| {
+            listener.borrow_mut().push((
+                old.map(|(path, val): (&[usize], &Dist)| (path.to_vec(), val.0)),
+                new.map(|(path, val): (&[usize], &Dist)| (path.to_vec(), val.0)),
+            ))
+        });
+        ptree.update_edge(0, 1, 1);
+        ptree.update_edge(2, 0, 5);
+        ptree.update_edge(1, 2, 0);
+        ptree.update_edge(0, 2, 1);
+        ptree.forbid_edge(2, 1);
+        ptree.disable_vertex(1);
+        assert_eq!(
+            *changes.borrow(),
+            [
+                (Some((vec![0, 1, 2, 0], 0)), Some((vec![0, 2, 1, 0], 0))),
+                (Some((vec![0, 2, 1, 0], 0)), Some((vec![0, 2, 1, 0], 1))),
+                (Some((vec![0, 2, 1, 0], 1)), Some((vec![0, 1, 2, 0], 1 + 5))),
+                (Some((vec![0, 1, 2, 0], 1 + 5)), None),
+            ]
+        );
+        assert_eq!(ptree.shortest_path(), None);
+        ptree.enable_vertex(1);
+        assert_eq!(
+            ptree.shortest_path(),
+            Some((vec![0, 1, 2, 0], &Dist(1 + 5)))
+        );
+        assert_eq!(changes.borrow().len(), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: Some(v) != self.tree.end()")]
+    fn disable_vertex_end_panicked_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> = Builder::new(3, 2, 0).end(3).build(Dist(0));
+        ptree.disable_vertex(3);
+    }
+
+    #[test]
+    fn accessors_test() {
+        let mut ptree: PlacementsTree<i64, i64, Dist> = PlacementsTree::new(3, 5, 1, Dist(0));
+        assert_eq!(ptree.n(), 3);
+        assert_eq!(ptree.k(), 3);
+        assert_eq!(ptree.key(), 1);
+        assert_eq!(ptree.end(), Some(1));
+        assert_eq!(*ptree.root_value(), Dist(0));
+        ptree.update_vertex(2, 7);
+        ptree.update_edge(3, 0, 4);
+        assert_eq!(*ptree.vertex(2), 7);
+        assert_eq!(*ptree.edge(3, 0), 4);
+        assert_eq!(ptree.vertices(), [0, 0, 7, 0]);
+        assert_eq!(ptree.edges()[3], [4, 0, 0, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: v <= self.n")]
+    fn vertex_panicked_test() {
+        let ptree: PlacementsTree<i64, i64, Dist> = PlacementsTree::new(2, 2, 0, Dist(0));
+        ptree.vertex(3);
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: v != u")]
+    fn edge_panicked_test() {
+        let ptree: PlacementsTree<i64, i64, Dist> = PlacementsTree::new(2, 2, 0, Dist(0));
+        ptree.edge(1, 1);
+    }
+
     #[test]
     #[should_panic(expected = "assertion failed: v <= self.n")]
     fn update_vertex_panicked_test() {
@@ -1,75 +1,157 @@
 pub trait Max {
     fn max() -> Self;
+
+    fn is_feasible(&self) -> bool {
+        true
+    }
 }
 
 impl Max for i8 {
     fn max() -> Self {
         i8::MAX
     }
+
+    fn is_feasible(&self) -> bool {
+        *self != i8::MAX
+    }
 }
 
 impl Max for u8 {
     fn max() -> Self {
         u8::MAX
     }
+
+    fn is_feasible(&self) -> bool {
+        *self != u8::MAX
+    }
 }
 
 impl Max for i16 {
     fn max() -> Self {
         i16::MAX
     }
+
+    fn is_feasible(&self) -> bool {
+        *self != i16::MAX
+    }
 }
 
 impl Max for u16 {
     fn max() -> Self {
         u16::MAX
     }
+
+    fn is_feasible(&self) -> bool {
+        *self != u16::MAX
+    }
 }
 
 impl Max for i32 {
     fn max() -> Self {
         i32::MAX
     }
+
+    fn is_feasible(&self) -> bool {
+        *self != i32::MAX
+    }
 }
 
 impl Max for u32 {
     fn max() -> Self {
         u32::MAX
     }
+
+    fn is_feasible(&self) -> bool {
+        *self != u32::MAX
+    }
 }
 
 impl Max for i64 {
     fn max() -> Self {
         i64::MAX
     }
+
+    fn is_feasible(&self) -> bool {
+        *self != i64::MAX
+    }
 }
 
 impl Max for u64 {
     fn max() -> Self {
         u64::MAX
     }
+
+    fn is_feasible(&self) -> bool {
+        *self != u64::MAX
+    }
 }
 
 impl Max for i128 {
     fn max() -> Self {
         i128::MAX
     }
+
+    fn is_feasible(&self) -> bool {
+        *self != i128::MAX
+    }
 }
 
 impl Max for u128 {
     fn max() -> Self {
         u128::MAX
     }
+
+    fn is_feasible(&self) -> bool {
+        *self != u128::MAX
+    }
 }
 
 impl Max for isize {
     fn max() -> Self {
         isize::MAX
     }
+
+    fn is_feasible(&self) -> bool {
+        *self != isize::MAX
+    }
 }
 
 impl Max for usize {
     fn max() -> Self {
         usize::MAX
     }
+
+    fn is_feasible(&self) -> bool {
+        *self != usize::MAX
+    }
+}
+
+impl Max for f32 {
+    fn max() -> Self {
+        f32::INFINITY
+    }
+
+    fn is_feasible(&self) -> bool {
+        *self < f32::INFINITY
+    }
+}
+
+impl Max for f64 {
+    fn max() -> Self {
+        f64::INFINITY
+    }
+
+    fn is_feasible(&self) -> bool {
+        *self < f64::INFINITY
+    }
+}
+
+impl<T> Max for Option<T> {
+    fn max() -> Self {
+        None
+    }
+
+    fn is_feasible(&self) -> bool {
+        self.is_some()
+    }
 }
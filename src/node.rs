@@ -1,14 +1,31 @@
+use crate::constraints::Constraints;
 use crate::fill::Fill;
-use crate::recalc::Recalc;
+use crate::graph::Graph;
+use crate::recalc::RecalcAt;
+use crate::stats::Stats;
+use crate::trace;
 use crate::Max;
-use std::collections::LinkedList;
+use std::cmp::Ordering;
+use std::collections::{HashSet, LinkedList};
 use std::ptr::NonNull;
 
+#[derive(PartialEq, Debug)]
+pub enum Mismatch<'a, D> {
+    Value(Vec<usize>, &'a D, D),
+    Masked(Vec<usize>, usize, usize),
+    Front(Vec<usize>, Vec<Vec<usize>>, Vec<Vec<usize>>),
+}
+
 pub struct Node<D> {
     parent: Option<NonNull<Node<D>>>,
     children: Vec<Box<Node<D>>>,
     key: usize,
     val: D,
+    depth: usize,
+    terminal: bool,
+    feasible: bool,
+    masked: usize,
+    front: Vec<NonNull<Node<D>>>,
 }
 
 impl<D> Node<D> {
@@ -21,6 +38,11 @@ impl<D> Node<D> {
             children: Vec::new(),
             key,
             val,
+            depth: 0,
+            terminal: false,
+            feasible: true,
+            masked: 0,
+            front: Vec::new(),
         })
     }
 
@@ -33,135 +55,614 @@ impl<D> Node<D> {
             children: Vec::new(),
             key,
             val: D::max(),
+            depth: self.depth + 1,
+            terminal: false,
+            feasible: true,
+            masked: 0,
+            front: Vec::new(),
         })
     }
 
-    pub fn root(n: usize, k: usize, key: usize, val: D) -> Box<Self>
+    pub fn val(&self) -> &D {
+        &self.val
+    }
+
+    pub fn key(&self) -> usize {
+        self.key
+    }
+
+    pub fn parent(&self) -> Option<&Self> {
+        self.parent.map(|parent| unsafe { &*parent.as_ptr() })
+    }
+
+    pub fn children(&self) -> &[Box<Self>] {
+        &self.children
+    }
+
+    #[cfg(test)]
+    fn root(n: usize, k: usize, key: usize, val: D) -> Box<Self>
+    where
+        D: Max,
+    {
+        Self::path(n, k, k, key, Some(key), &Constraints::new(n), val)
+    }
+
+    pub fn path(
+        n: usize,
+        k: usize,
+        min_k: usize,
+        key: usize,
+        end: Option<usize>,
+        constraints: &Constraints,
+        val: D,
+    ) -> Box<Self>
     where
         D: Max,
     {
         assert!(key <= n);
-        let k = k.min(n);
-        let mut root = Self::new(key, val);
-        for key in 0..key {
-            root.insert(key, k);
-        }
-        for key in key + 1..=n {
-            root.insert(key, k);
+        if let Some(end) = end {
+            assert!(end <= n);
         }
-        root.finish(key);
+        let k = k.min(limit(n, key, end));
+        let _span = trace::span!("path", n = n, k = k, min_k = min_k, key = key);
+        let mut root = Self::new(key, val);
+        let mut placed = root.placed(end, constraints);
+        let missing = constraints.missing(&placed);
+        root.spawn(&mut placed, missing, k, constraints);
+        root.complete(end, min_k.min(k), k, constraints);
         root
     }
 
-    fn insert(&mut self, key: usize, k: usize)
+    pub fn insert(
+        &mut self,
+        n: usize,
+        end: Option<usize>,
+        k: usize,
+        constraints: &Constraints,
+    ) -> Vec<NonNull<Self>>
     where
         D: Max,
     {
-        if k > 0 {
-            let mut node = self.child(key);
-            let mut index = self.children.len();
-            for (i, child) in self.children.iter_mut().enumerate() {
-                if key < child.key {
-                    index = i;
-                    break;
+        let mut inserted = Vec::new();
+        self.graft(n, end, k, constraints, &mut inserted);
+        inserted
+    }
+
+    fn graft(
+        &mut self,
+        n: usize,
+        end: Option<usize>,
+        k: usize,
+        constraints: &Constraints,
+        inserted: &mut Vec<NonNull<Self>>,
+    ) where
+        D: Max,
+    {
+        if self.depth < k {
+            for child in self.children.iter_mut() {
+                if Some(child.key) != end {
+                    child.graft(n, end, k, constraints, inserted);
                 }
-                child.insert(key, k - 1);
-                node.insert(child.key, k - 1);
             }
-            if index < self.children.len() {
-                for child in self.children.iter_mut() {
-                    child.insert(key, k - 1);
-                    node.insert(child.key, k - 1);
+            let mut placed = self.placed(end, constraints);
+            let missing = constraints.missing(&placed);
+            if let Some(node) = self.sprout(n, &mut placed, missing, k, constraints) {
+                inserted.push(NonNull::from(&*node));
+                self.children.push(node);
+            }
+        }
+    }
+
+    fn spawn(&mut self, placed: &mut [bool], missing: usize, k: usize, constraints: &Constraints)
+    where
+        D: Max,
+    {
+        if self.depth < k {
+            for key in 0..placed.len() {
+                if let Some(child) = self.sprout(key, placed, missing, k, constraints) {
+                    self.children.push(child);
                 }
-                self.children.insert(index, node);
-            } else {
-                self.children.insert(index, node);
             }
         }
     }
 
-    fn finish(&mut self, key: usize)
+    fn sprout(
+        &self,
+        key: usize,
+        placed: &mut [bool],
+        missing: usize,
+        k: usize,
+        constraints: &Constraints,
+    ) -> Option<Box<Self>>
     where
         D: Max,
     {
-        if self.children.is_empty() {
-            self.children.insert(0, self.child(key));
-        } else {
-            for child in self.children.iter_mut() {
-                child.finish(key);
+        if placed[key] || !constraints.allows(key, placed) {
+            return None;
+        }
+        let missing = missing - constraints.is_required(key) as usize;
+        if missing > k - self.depth - 1 {
+            return None;
+        }
+        let mut child = self.child(key);
+        placed[key] = true;
+        child.spawn(placed, missing, k, constraints);
+        placed[key] = false;
+        Some(child)
+    }
+
+    fn placed(&self, end: Option<usize>, constraints: &Constraints) -> Vec<bool> {
+        let mut placed = vec![false; constraints.n() + 1];
+        if let Some(end) = end {
+            placed[end] = true;
+        }
+        let mut node = Some(NonNull::from(self));
+        while let Some(current) = node {
+            unsafe {
+                placed[current.as_ref().key] = true;
+                node = current.as_ref().parent;
+            }
+        }
+        placed
+    }
+
+    pub fn complete(
+        &mut self,
+        end: Option<usize>,
+        min_k: usize,
+        k: usize,
+        constraints: &Constraints,
+    ) where
+        D: Max,
+    {
+        let missing = constraints.missing(&self.placed(end, constraints));
+        match end {
+            Some(end) => self.finish(end, min_k, k, missing, constraints),
+            None => self.mark(min_k, missing, constraints),
+        }
+    }
+
+    fn finish(
+        &mut self,
+        end: usize,
+        min_k: usize,
+        k: usize,
+        missing: usize,
+        constraints: &Constraints,
+    ) where
+        D: Max,
+    {
+        if self.depth < k {
+            for child in self.children.iter_mut().filter(|child| child.key != end) {
+                let missing = missing - constraints.is_required(child.key) as usize;
+                child.finish(end, min_k, k, missing, constraints);
+            }
+        }
+        let finished = self.children.first().is_some_and(|child| child.key == end);
+        let closing = self.depth >= min_k && missing == 0;
+        if closing && !finished {
+            let mut child = self.child(end);
+            child.terminal = true;
+            self.children.insert(0, child);
+        } else if !closing && finished {
+            self.children.remove(0);
+        }
+    }
+
+    fn mark(&mut self, min_k: usize, missing: usize, constraints: &Constraints) {
+        self.terminal = self.depth >= min_k && missing == 0;
+        for child in self.children.iter_mut() {
+            let missing = missing - constraints.is_required(child.key) as usize;
+            child.mark(min_k, missing, constraints);
+        }
+    }
+
+    pub fn extend(&mut self, end: Option<usize>, k: usize, depth: usize, constraints: &Constraints)
+    where
+        D: Max,
+    {
+        let mut placed = self.placed(end, constraints);
+        let missing = constraints.missing(&placed);
+        if self.depth < k {
+            let mut children = std::mem::take(&mut self.children).into_iter().peekable();
+            if let Some(child) = children.next_if(|child| Some(child.key) == end) {
+                self.children.push(child);
+            }
+            for key in 0..placed.len() {
+                if let Some(mut child) = children.next_if(|child| child.key == key) {
+                    child.extend(end, k, depth, constraints);
+                    self.children.push(child);
+                } else if let Some(child) =
+                    self.sprout(key, &mut placed, missing, k + depth, constraints)
+                {
+                    self.children.push(child);
+                }
             }
+        } else {
+            self.children.clear();
+            self.spawn(&mut placed, missing, k + depth, constraints);
+        }
+    }
+
+    pub fn truncate(&mut self, end: Option<usize>, k: usize, constraints: &Constraints) {
+        let missing = constraints.missing(&self.placed(end, constraints));
+        self.prune(end, k, missing, constraints);
+    }
+
+    fn prune(&mut self, end: Option<usize>, k: usize, missing: usize, constraints: &Constraints) {
+        if self.depth < k {
+            self.children.retain_mut(|child| {
+                if Some(child.key) == end {
+                    return true;
+                }
+                let missing = missing - constraints.is_required(child.key) as usize;
+                if missing > k - child.depth {
+                    return false;
+                }
+                child.prune(end, k, missing, constraints);
+                true
+            });
+        } else {
+            self.children.clear();
+        }
+    }
+
+    pub fn mask(&mut self)
+    where
+        D: PartialOrd,
+    {
+        self.masked += 1;
+        for child in self.children.iter_mut() {
+            child.mask();
         }
+        self.refresh();
     }
 
-    pub unsafe fn recalc_children<V, E>(&mut self, vertices: &Vec<V>, edges: &Vec<Vec<E>>) -> &D
+    pub fn unmask(&mut self)
     where
-        D: Recalc<V, E> + PartialOrd,
+        D: PartialOrd,
     {
-        assert!(!self.children.is_empty());
-        let mut children = self.children.iter_mut();
-        let mut shortest = children
-            .next()
-            .map(|child| child.recalc(vertices, edges))
-            .unwrap();
-        for child in children {
-            let recalced = child.recalc(vertices, edges);
-            if recalced < shortest {
-                shortest = recalced;
+        self.masked -= 1;
+        for child in self.children.iter_mut() {
+            child.unmask();
+        }
+        self.refresh();
+    }
+
+    pub fn remask(&mut self, disabled: &[bool], forbidden: &[Vec<bool>]) {
+        self.masked = disabled[self.key] as usize;
+        if let Some(parent) = self.parent {
+            unsafe {
+                self.masked += parent.as_ref().masked;
+                self.masked += forbidden[parent.as_ref().key][self.key] as usize;
             }
         }
-        shortest
+        for child in self.children.iter_mut() {
+            child.remask(disabled, forbidden);
+        }
     }
 
-    pub unsafe fn recalc<V, E>(&mut self, vertices: &Vec<V>, edges: &Vec<Vec<E>>) -> &D
+    pub unsafe fn recalc<V, E>(
+        &mut self,
+        vertices: &Vec<V>,
+        edges: &Vec<Vec<E>>,
+        stats: &mut Stats,
+    ) -> Option<&D>
     where
-        D: Recalc<V, E> + PartialOrd,
+        D: RecalcAt<V, E> + PartialOrd + Max,
     {
+        stats.recalced += 1;
+        if self.children.is_empty() {
+            stats.leaves += 1;
+        }
         if let Some(parent) = self.parent {
             let parent_key = parent.as_ref().key;
             let parent_val = &parent.as_ref().val;
             let vertex = &vertices[parent_key];
             let edge = &edges[parent_key][self.key];
-            self.val = parent_val.recalc(vertex, edge);
+            let next = &vertices[self.key];
+            self.val = parent_val.recalc_at(self.depth, parent_key, self.key, vertex, edge, next);
+            self.feasible = self.val.is_feasible();
         }
-        if self.children.is_empty() {
-            &self.val
-        } else {
-            let mut children = self.children.iter_mut();
-            let mut shortest = children
-                .next()
-                .map(|child| child.recalc(vertices, edges))
-                .unwrap();
-            for child in children {
-                let recalced = child.recalc(vertices, edges);
-                if recalced < shortest {
-                    shortest = recalced;
+        for child in self.children.iter_mut() {
+            child.recalc(vertices, edges, stats);
+        }
+        self.refresh();
+        self.best().map(Self::val)
+    }
+
+    pub unsafe fn recalc_descendants<V, E>(
+        &mut self,
+        k: usize,
+        vertices: &Vec<V>,
+        edges: &Vec<Vec<E>>,
+        stats: &mut Stats,
+    ) -> Option<&D>
+    where
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        for child in self.children.iter_mut() {
+            if k > 0 {
+                child.recalc_descendants(k - 1, vertices, edges, stats);
+            } else {
+                stats.seeded += 1;
+                child.recalc(vertices, edges, stats);
+            }
+        }
+        self.refresh();
+        least(self.front.iter().map(|node| &*node.as_ptr()).collect()).map(Self::val)
+    }
+
+    pub fn refresh(&mut self)
+    where
+        D: PartialOrd,
+    {
+        let mut front = Vec::new();
+        for child in self.children.iter() {
+            for node in child.front() {
+                dominate(&mut front, node, |node| &node.val);
+            }
+        }
+        self.front = front.into_iter().map(NonNull::from).collect();
+    }
+
+    pub fn refresh_all(&mut self)
+    where
+        D: PartialOrd,
+    {
+        for child in self.children.iter_mut() {
+            child.refresh_all();
+        }
+        self.refresh();
+    }
+
+    pub unsafe fn propagate(nodes: &[NonNull<Self>])
+    where
+        D: PartialOrd,
+    {
+        let mut levels: Vec<Vec<NonNull<Self>>> = Vec::new();
+        let mut parents = HashSet::new();
+        for node in nodes.iter() {
+            if let Some(parent) = node.as_ref().parent {
+                let depth = parent.as_ref().depth;
+                if levels.len() <= depth {
+                    levels.resize(depth + 1, Vec::new());
+                }
+                if parents.insert(parent) {
+                    levels[depth].push(parent);
+                }
+            }
+        }
+        for depth in (0..levels.len()).rev() {
+            for node in std::mem::take(&mut levels[depth]) {
+                (*node.as_ptr()).refresh();
+                if let Some(parent) = node.as_ref().parent {
+                    if parents.insert(parent) {
+                        levels[depth - 1].push(parent);
+                    }
                 }
             }
-            shortest
         }
     }
 
+    pub fn best(&self) -> Option<&Self>
+    where
+        D: PartialOrd,
+    {
+        least(self.front())
+    }
+
+    pub unsafe fn best_of<'a>(nodes: &[NonNull<Self>]) -> Option<&'a Self>
+    where
+        D: PartialOrd,
+    {
+        let mut front = Vec::new();
+        for node in nodes.iter() {
+            for node in (*node.as_ptr()).front() {
+                dominate(&mut front, node, |node| &node.val);
+            }
+        }
+        least(front)
+    }
+
+    pub fn front(&self) -> Vec<&Self>
+    where
+        D: PartialOrd,
+    {
+        let mut front = self.placement().map(|_| vec![self]).unwrap_or_default();
+        for node in self.front.iter() {
+            dominate(&mut front, unsafe { &*node.as_ptr() }, |node| &node.val);
+        }
+        front
+    }
+
+    pub fn validate<'a, V, E>(
+        &'a self,
+        val: &D,
+        masked: usize,
+        graph: &Graph<V, E>,
+        mismatches: &mut Vec<Mismatch<'a, D>>,
+    ) -> Vec<&'a Self>
+    where
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        if self.masked != masked {
+            mismatches.push(Mismatch::Masked(self.keys(), self.masked, masked));
+        }
+        let mut front = Vec::new();
+        for child in self.children.iter() {
+            let vertex = &graph.vertices[self.key];
+            let edge = &graph.edges[self.key][child.key];
+            let next = &graph.vertices[child.key];
+            let expected = val.recalc_at(child.depth, self.key, child.key, vertex, edge, next);
+            let masked = masked
+                + graph.disabled[child.key] as usize
+                + graph.forbidden[self.key][child.key] as usize;
+            for node in child.validate(&expected, masked, graph, mismatches) {
+                dominate(&mut front, node, |node| &node.val);
+            }
+            if !same(&child.val, &expected) || child.feasible != expected.is_feasible() {
+                mismatches.push(Mismatch::Value(child.keys(), &child.val, expected));
+            }
+        }
+        let cached = self.front.iter().map(|node| unsafe { &*node.as_ptr() });
+        if !cached
+            .clone()
+            .map(NonNull::from)
+            .eq(front.iter().copied().map(NonNull::from))
+        {
+            mismatches.push(Mismatch::Front(
+                self.keys(),
+                cached.map(Self::keys).collect(),
+                front.iter().map(|node| node.keys()).collect(),
+            ));
+        }
+        let mut own = self.placement().map(|_| vec![self]).unwrap_or_default();
+        for node in front {
+            dominate(&mut own, node, |node| &node.val);
+        }
+        own
+    }
+
     #[cfg(test)]
-    fn placements(&self) -> LinkedList<LinkedList<usize>> {
-        if self.children.is_empty() {
-            LinkedList::from([LinkedList::from([self.key])])
-        } else {
-            let mut placements = LinkedList::new();
+    pub fn shortest(&self) -> Option<&D>
+    where
+        D: PartialOrd,
+    {
+        self.argmin().map(Self::val)
+    }
+
+    #[cfg(test)]
+    pub fn argmin(&self) -> Option<&Self>
+    where
+        D: PartialOrd,
+    {
+        let mut front = Vec::new();
+        self.scan(&mut front);
+        least(front)
+    }
+
+    #[cfg(test)]
+    fn scan<'a>(&'a self, front: &mut Vec<&'a Self>)
+    where
+        D: PartialOrd,
+    {
+        if self.placement().is_some() {
+            dominate(front, self, |node| &node.val);
+        }
+        for child in self.children.iter() {
+            child.scan(front);
+        }
+    }
+
+    pub fn shortest_at(&self, depth: usize) -> Option<&D>
+    where
+        D: PartialOrd,
+    {
+        let mut front = Vec::new();
+        self.front_at(depth, &mut front);
+        least(front).map(Self::val)
+    }
+
+    fn front_at<'a>(&'a self, depth: usize, front: &mut Vec<&'a Self>)
+    where
+        D: PartialOrd,
+    {
+        if self.depth < depth {
             for child in self.children.iter() {
-                placements.append(
-                    &mut child
-                        .placements()
-                        .into_iter()
-                        .map(|mut placements| {
-                            let mut prepended = LinkedList::from([self.key]);
-                            prepended.append(&mut placements);
-                            prepended
-                        })
-                        .collect::<LinkedList<LinkedList<usize>>>(),
-                );
+                child.front_at(depth, front);
+            }
+        } else if self.placement().is_some() {
+            dominate(front, self, |node| &node.val);
+        }
+    }
+
+    fn placement(&self) -> Option<&D> {
+        if self.terminal && self.feasible && self.masked == 0 {
+            Some(&self.val)
+        } else {
+            None
+        }
+    }
+
+    pub fn descends(&self, nodes: &HashSet<NonNull<Self>>) -> bool {
+        let mut node = self.parent;
+        while let Some(current) = node {
+            if nodes.contains(&current) {
+                return true;
+            }
+            node = unsafe { current.as_ref().parent };
+        }
+        false
+    }
+
+    pub fn keys(&self) -> Vec<usize> {
+        let mut keys = Vec::with_capacity(self.depth + 1);
+        let mut node = Some(NonNull::from(self));
+        while let Some(current) = node {
+            unsafe {
+                keys.push(current.as_ref().key);
+                node = current.as_ref().parent;
             }
-            placements
         }
+        keys.reverse();
+        keys
+    }
+
+    #[cfg(test)]
+    fn placements(&self) -> LinkedList<LinkedList<usize>> {
+        let mut placements = LinkedList::new();
+        if self.terminal {
+            placements.push_back(LinkedList::from([self.key]));
+        }
+        for child in self.children.iter() {
+            placements.append(
+                &mut child
+                    .placements()
+                    .into_iter()
+                    .map(|mut placements| {
+                        let mut prepended = LinkedList::from([self.key]);
+                        prepended.append(&mut placements);
+                        prepended
+                    })
+                    .collect::<LinkedList<LinkedList<usize>>>(),
+            );
+        }
+        placements
+    }
+}
+
+fn same<D>(val: &D, expected: &D) -> bool
+where
+    D: PartialOrd,
+{
+    val == expected || (val.partial_cmp(val).is_none() && expected.partial_cmp(expected).is_none())
+}
+
+pub fn limit(n: usize, key: usize, end: Option<usize>) -> usize {
+    match end {
+        Some(end) if end != key => n - 1,
+        _ => n,
+    }
+}
+
+pub fn dominate<T, D, F>(front: &mut Vec<T>, item: T, val: F)
+where
+    D: PartialOrd,
+    F: Fn(&T) -> &D,
+{
+    if front.iter().any(|other| val(other) <= val(&item)) {
+        return;
+    }
+    front.retain(|other| val(&item).partial_cmp(val(other)) != Some(Ordering::Less));
+    front.push(item);
+}
+
+pub fn least<T>(mut front: Vec<T>) -> Option<T> {
+    if front.len() == 1 {
+        front.pop()
+    } else {
+        None
     }
 }
 
@@ -192,6 +693,7 @@ impl<D> Fill<Vec<Vec<LinkedList<NonNull<Node<D>>>>>> for Node<D> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::recalc::Recalc;
 
     #[derive(PartialEq, Eq, PartialOrd, Debug)]
     struct Dist(i64);
@@ -408,45 +910,603 @@ mod tests {
     }
 
     #[test]
-    fn fill_vertices_test() {
-        let root = Node::root(4, 2, 0, Dist(0));
+    fn placements_open_test() {
+        let root = Node::path(3, 0, 0, 0, None, &Constraints::new(3), Dist(0));
 
-        let mut vertices = vec![LinkedList::new(); 5];
-        root.fill(&mut vertices);
+        assert_eq!(
+            root.placements()
+                .into_iter()
+                .map(|placement| placement.into_iter().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            [[0]]
+        );
 
-        assert_eq!(vertices[0].len(), 1);
-        assert_eq!(vertices[1].len(), 4);
-        assert_eq!(vertices[2].len(), 4);
-        assert_eq!(vertices[3].len(), 4);
-        assert_eq!(vertices[4].len(), 4);
+        let root = Node::path(3, 2, 2, 1, None, &Constraints::new(3), Dist(0));
 
-        for key in 0..vertices.len() {
-            for vertex in vertices[key].iter() {
-                unsafe {
-                    assert_eq!(vertex.as_ref().key, key);
-                }
+        assert_eq!(
+            root.placements()
+                .into_iter()
+                .map(|placement| placement.into_iter().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            [
+                [1, 0, 2],
+                [1, 0, 3],
+                [1, 2, 0],
+                [1, 2, 3],
+                [1, 3, 0],
+                [1, 3, 2],
+            ]
+        );
+
+        for k in 0..=3 {
+            for depth in 0..=3 - k {
+                let mut root = Node::path(3, k, k, 0, None, &Constraints::new(3), Dist(0));
+                root.extend(None, k, depth, &Constraints::new(3));
+                root.complete(None, k + depth, k + depth, &Constraints::new(3));
+
+                let expected = Node::path(
+                    3,
+                    k + depth,
+                    k + depth,
+                    0,
+                    None,
+                    &Constraints::new(3),
+                    Dist(0),
+                );
+
+                assert_eq!(root.placements(), expected.placements());
+
+                root.truncate(None, k, &Constraints::new(3));
+                root.complete(None, k, k, &Constraints::new(3));
+
+                let expected = Node::path(3, k, k, 0, None, &Constraints::new(3), Dist(0));
+
+                assert_eq!(root.placements(), expected.placements());
             }
         }
     }
 
     #[test]
-    fn fill_edges_test() {
-        let root = Node::root(4, 2, 0, Dist(0));
+    fn placements_ended_test() {
+        let root = Node::path(3, 2, 2, 0, Some(3), &Constraints::new(3), Dist(0));
 
-        let mut edges = vec![vec![LinkedList::new(); 5]; 5];
-        root.fill(&mut edges);
+        assert_eq!(
+            root.placements()
+                .into_iter()
+                .map(|placement| placement.into_iter().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            [[0, 1, 2, 3], [0, 2, 1, 3]]
+        );
 
-        assert_eq!(edges[0][0].len(), 0);
-        assert_eq!(edges[0][1].len(), 1);
-        assert_eq!(edges[0][2].len(), 1);
-        assert_eq!(edges[0][3].len(), 1);
-        assert_eq!(edges[0][4].len(), 1);
-        assert_eq!(edges[1][0].len(), 3);
-        assert_eq!(edges[1][1].len(), 0);
-        assert_eq!(edges[1][2].len(), 1);
-        assert_eq!(edges[1][3].len(), 1);
-        assert_eq!(edges[1][4].len(), 1);
-        assert_eq!(edges[2][0].len(), 3);
+        let root = Node::path(3, 1, 1, 2, Some(0), &Constraints::new(3), Dist(0));
+
+        assert_eq!(
+            root.placements()
+                .into_iter()
+                .map(|placement| placement.into_iter().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            [[2, 1, 0], [2, 3, 0]]
+        );
+
+        let root = Node::path(1, 1, 1, 0, Some(1), &Constraints::new(1), Dist(0));
+
+        assert_eq!(
+            root.placements()
+                .into_iter()
+                .map(|placement| placement.into_iter().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            [[0, 1]]
+        );
+
+        for k in 0..=2 {
+            for depth in 0..=2 - k {
+                let mut root = Node::path(3, k, k, 1, Some(2), &Constraints::new(3), Dist(0));
+                root.extend(Some(2), k, depth, &Constraints::new(3));
+                root.complete(Some(2), k + depth, k + depth, &Constraints::new(3));
+
+                let expected = Node::path(
+                    3,
+                    k + depth,
+                    k + depth,
+                    1,
+                    Some(2),
+                    &Constraints::new(3),
+                    Dist(0),
+                );
+
+                assert_eq!(root.placements(), expected.placements());
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed: end <= n")]
+    fn path_panicked_test() {
+        Node::path(2, 2, 2, 0, Some(3), &Constraints::new(2), Dist(0));
+    }
+
+    #[test]
+    fn placements_inserted_test() {
+        for k in 0..=3 {
+            let mut root = Node::root(2, k, 0, Dist(0));
+            root.insert(3, Some(0), k.min(2), &Constraints::new(3));
+            root.complete(Some(0), k.min(2), k.min(2), &Constraints::new(3));
+
+            let expected = Node::root(3, k.min(2), 0, Dist(0));
+
+            assert_eq!(root.placements(), expected.placements());
+        }
+    }
+
+    #[test]
+    fn placements_ranged_test() {
+        let root = Node::path(3, 2, 0, 0, Some(0), &Constraints::new(3), Dist(0));
+
+        assert_eq!(
+            root.placements()
+                .into_iter()
+                .map(|placement| placement.into_iter().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            [
+                vec![0, 0],
+                vec![0, 1, 0],
+                vec![0, 1, 2, 0],
+                vec![0, 1, 3, 0],
+                vec![0, 2, 0],
+                vec![0, 2, 1, 0],
+                vec![0, 2, 3, 0],
+                vec![0, 3, 0],
+                vec![0, 3, 1, 0],
+                vec![0, 3, 2, 0],
+            ]
+        );
+
+        let root = Node::path(3, 2, 1, 0, None, &Constraints::new(3), Dist(0));
+
+        assert_eq!(
+            root.placements()
+                .into_iter()
+                .map(|placement| placement.into_iter().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            [
+                vec![0, 1],
+                vec![0, 1, 2],
+                vec![0, 1, 3],
+                vec![0, 2],
+                vec![0, 2, 1],
+                vec![0, 2, 3],
+                vec![0, 3],
+                vec![0, 3, 1],
+                vec![0, 3, 2],
+            ]
+        );
+
+        for k in 1..=3 {
+            for depth in 0..=3 - k {
+                let mut root = Node::path(3, k, 1, 0, Some(0), &Constraints::new(3), Dist(0));
+                root.extend(Some(0), k, depth, &Constraints::new(3));
+                root.complete(Some(0), 1, k + depth, &Constraints::new(3));
+
+                let expected =
+                    Node::path(3, k + depth, 1, 0, Some(0), &Constraints::new(3), Dist(0));
+
+                assert_eq!(root.placements(), expected.placements());
+
+                root.truncate(Some(0), k, &Constraints::new(3));
+                root.complete(Some(0), 1, k, &Constraints::new(3));
+
+                let expected = Node::path(3, k, 1, 0, Some(0), &Constraints::new(3), Dist(0));
+
+                assert_eq!(root.placements(), expected.placements());
+
+                root.insert(4, Some(0), k, &Constraints::new(4));
+                root.complete(Some(0), 1, k, &Constraints::new(4));
+
+                let expected = Node::path(4, k, 1, 0, Some(0), &Constraints::new(4), Dist(0));
+
+                assert_eq!(root.placements(), expected.placements());
+            }
+        }
+    }
+
+    #[test]
+    fn placements_preceded_test() {
+        let mut constraints = Constraints::new(3);
+        constraints.precede(1, 3);
+
+        let root = Node::path(3, 3, 3, 0, Some(0), &constraints, Dist(0));
+
+        assert_eq!(
+            root.placements()
+                .into_iter()
+                .map(|placement| placement.into_iter().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            [[0, 1, 2, 3, 0], [0, 1, 3, 2, 0], [0, 2, 1, 3, 0]]
+        );
+
+        let root = Node::path(3, 2, 2, 0, None, &constraints, Dist(0));
+
+        assert_eq!(
+            root.placements()
+                .into_iter()
+                .map(|placement| placement.into_iter().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            [[0, 1, 2], [0, 1, 3], [0, 2, 1]]
+        );
+
+        for k in 0..=3 {
+            for depth in 0..=3 - k {
+                let mut root = Node::path(3, k, k, 0, Some(0), &constraints, Dist(0));
+                root.extend(Some(0), k, depth, &constraints);
+                root.complete(Some(0), k + depth, k + depth, &constraints);
+
+                let expected =
+                    Node::path(3, k + depth, k + depth, 0, Some(0), &constraints, Dist(0));
+
+                assert_eq!(root.placements(), expected.placements());
+            }
+        }
+
+        let mut inserted = constraints.clone();
+        inserted.push();
+
+        for k in 0..=3 {
+            let mut root = Node::path(3, k, k, 0, Some(0), &constraints, Dist(0));
+            root.insert(4, Some(0), k, &inserted);
+            root.complete(Some(0), k, k, &inserted);
+
+            let expected = Node::path(4, k, k, 0, Some(0), &inserted, Dist(0));
+
+            assert_eq!(root.placements(), expected.placements());
+        }
+    }
+
+    #[test]
+    fn placements_required_test() {
+        let mut constraints = Constraints::new(3);
+        constraints.require(3);
+
+        let root = Node::path(3, 2, 2, 0, Some(0), &constraints, Dist(0));
+
+        assert_eq!(
+            root.placements()
+                .into_iter()
+                .map(|placement| placement.into_iter().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            [[0, 1, 3, 0], [0, 2, 3, 0], [0, 3, 1, 0], [0, 3, 2, 0]]
+        );
+
+        let root = Node::path(3, 2, 1, 0, None, &constraints, Dist(0));
+
+        assert_eq!(
+            root.placements()
+                .into_iter()
+                .map(|placement| placement.into_iter().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            [
+                vec![0, 1, 3],
+                vec![0, 2, 3],
+                vec![0, 3],
+                vec![0, 3, 1],
+                vec![0, 3, 2],
+            ]
+        );
+
+        constraints.require(2);
+
+        let root = Node::path(3, 1, 1, 0, Some(0), &constraints, Dist(0));
+
+        assert!(root.children.is_empty());
+
+        for k in 0..=3 {
+            for depth in 0..=3 - k {
+                let mut root = Node::path(3, k, 1, 0, Some(0), &constraints, Dist(0));
+                root.extend(Some(0), k, depth, &constraints);
+                root.complete(Some(0), 1, k + depth, &constraints);
+
+                let expected = Node::path(3, k + depth, 1, 0, Some(0), &constraints, Dist(0));
+
+                assert_eq!(root.placements(), expected.placements());
+
+                root.truncate(Some(0), k, &constraints);
+                root.complete(Some(0), 1, k, &constraints);
+
+                let expected = Node::path(3, k, 1, 0, Some(0), &constraints, Dist(0));
+
+                assert_eq!(root.placements(), expected.placements());
+            }
+        }
+    }
+
+    #[test]
+    fn shortest_at_test() {
+        let mut root = Node::path(2, 2, 0, 0, Some(0), &Constraints::new(2), Dist(0));
+
+        let vertices = vec![0, 0, 0];
+
+        let edges = vec![vec![1, 1, 2], vec![3, 0, 4], vec![5, 6, 0]];
+
+        unsafe {
+            assert_eq!(
+                *root
+                    .recalc(&vertices, &edges, &mut Stats::default())
+                    .unwrap(),
+                Dist(1)
+            );
+        }
+
+        assert_eq!(*root.shortest_at(1).unwrap(), Dist(1));
+        assert_eq!(*root.shortest_at(2).unwrap(), Dist(4));
+        assert_eq!(*root.shortest_at(3).unwrap(), Dist(10));
+        assert_eq!(root.shortest_at(4), None);
+    }
+
+    #[test]
+    fn front_test() {
+        #[derive(PartialEq, Debug)]
+        struct Pair(i64, i64);
+
+        impl PartialOrd for Pair {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                match (self.0 <= other.0, self.1 <= other.1) {
+                    (true, true) if self == other => Some(Ordering::Equal),
+                    (true, true) => Some(Ordering::Less),
+                    (false, false) => Some(Ordering::Greater),
+                    _ => None,
+                }
+            }
+        }
+
+        impl Max for Pair {
+            fn max() -> Self {
+                Pair(i64::MAX, i64::MAX)
+            }
+        }
+
+        let mut root = Node::root(2, 2, 0, Pair(0, 0));
+        root.children[0].children[0].children[0].val = Pair(3, 1);
+        root.children[1].children[0].children[0].val = Pair(1, 3);
+
+        root.refresh_all();
+
+        assert_eq!(
+            root.front()
+                .into_iter()
+                .map(|node| (node.keys(), &node.val))
+                .collect::<Vec<_>>(),
+            [
+                (vec![0, 1, 2, 0], &Pair(3, 1)),
+                (vec![0, 2, 1, 0], &Pair(1, 3))
+            ]
+        );
+
+        root.children[0].children[0].children[0].val = Pair(1, 2);
+
+        root.refresh_all();
+
+        assert_eq!(
+            root.front()
+                .into_iter()
+                .map(|node| (node.keys(), &node.val))
+                .collect::<Vec<_>>(),
+            [(vec![0, 1, 2, 0], &Pair(1, 2))]
+        );
+
+        root.children[1].mask();
+        root.children[0].children[0].children[0].val = Pair(1, 3);
+
+        root.refresh_all();
+
+        assert_eq!(
+            root.front()
+                .into_iter()
+                .map(|node| (node.keys(), &node.val))
+                .collect::<Vec<_>>(),
+            [(vec![0, 1, 2, 0], &Pair(1, 3))]
+        );
+    }
+
+    #[test]
+    fn argmin_test() {
+        let mut root = Node::root(2, 2, 0, Dist(0));
+
+        assert_eq!(root.argmin().unwrap().keys(), [0, 1, 2, 0]);
+
+        let vertices = vec![0, 0, 0];
+
+        let edges = vec![vec![0, 1, 2], vec![3, 0, 4], vec![5, 6, 0]];
+
+        unsafe {
+            root.recalc(&vertices, &edges, &mut Stats::default());
+        }
+
+        assert_eq!(root.argmin().unwrap().keys(), [0, 1, 2, 0]);
+        assert_eq!(root.argmin().unwrap().val, Dist(10));
+
+        root.children[0].mask();
+
+        assert_eq!(root.argmin().unwrap().keys(), [0, 2, 1, 0]);
+        assert_eq!(root.argmin().unwrap().val, Dist(11));
+
+        root.children[1].mask();
+
+        assert!(root.argmin().is_none());
+    }
+
+    #[test]
+    fn descends_test() {
+        let root = Node::root(2, 2, 0, Dist(0));
+        let nodes = HashSet::from([NonNull::from(&*root.children[0])]);
+
+        assert!(root.children[0].children[0].descends(&nodes));
+        assert!(root.children[0].children[0].children[0].descends(&nodes));
+        assert!(!root.children[0].descends(&nodes));
+        assert!(!root.children[1].children[0].descends(&nodes));
+        assert!(!root.descends(&nodes));
+    }
+
+    #[test]
+    fn mask_test() {
+        let mut root = Node::root(2, 2, 0, Dist(0));
+
+        let vertices = vec![0, 0, 0];
+
+        let edges = vec![vec![0, 1, 2], vec![3, 0, 4], vec![5, 6, 0]];
+
+        root.children[0].children[0].mask();
+
+        unsafe {
+            assert_eq!(
+                *root
+                    .recalc(&vertices, &edges, &mut Stats::default())
+                    .unwrap(),
+                Dist(11)
+            );
+        }
+
+        root.children[1].mask();
+
+        unsafe {
+            assert_eq!(root.recalc(&vertices, &edges, &mut Stats::default()), None);
+            assert_eq!(root.children[0].children[0].children[0].val, Dist(10));
+        }
+
+        root.children[0].children[0].unmask();
+        root.remask(
+            &[false, false, true],
+            &[vec![false; 3], vec![false; 3], vec![false; 3]],
+        );
+
+        unsafe {
+            assert_eq!(root.recalc(&vertices, &edges, &mut Stats::default()), None);
+        }
+
+        root.remask(
+            &[false; 3],
+            &[vec![false; 3], vec![false, false, true], vec![false; 3]],
+        );
+
+        unsafe {
+            assert_eq!(
+                *root
+                    .recalc(&vertices, &edges, &mut Stats::default())
+                    .unwrap(),
+                Dist(11)
+            );
+        }
+
+        root.remask(
+            &[false; 3],
+            &[vec![false; 3], vec![false; 3], vec![false; 3]],
+        );
+
+        unsafe {
+            assert_eq!(
+                *root
+                    .recalc(&vertices, &edges, &mut Stats::default())
+                    .unwrap(),
+                Dist(10)
+            );
+        }
+    }
+
+    #[test]
+    fn placements_extended_test() {
+        for k in 0..=3 {
+            for depth in 0..=3 - k {
+                let mut root = Node::root(3, k, 0, Dist(0));
+                root.extend(Some(0), k, depth, &Constraints::new(3));
+                root.complete(Some(0), k + depth, k + depth, &Constraints::new(3));
+
+                let expected = Node::root(3, k + depth, 0, Dist(0));
+
+                assert_eq!(root.placements(), expected.placements());
+            }
+        }
+    }
+
+    #[test]
+    fn placements_truncated_test() {
+        for k in 0..=3 {
+            for depth in 0..=k {
+                let mut root = Node::root(3, k, 1, Dist(0));
+                root.truncate(Some(1), depth, &Constraints::new(3));
+                root.complete(Some(1), depth, depth, &Constraints::new(3));
+
+                let expected = Node::root(3, depth, 1, Dist(0));
+
+                assert_eq!(root.placements(), expected.placements());
+            }
+        }
+    }
+
+    #[test]
+    fn shortest_test() {
+        let mut root = Node::root(2, 2, 0, Dist(0));
+
+        let vertices = vec![0, 0, 0];
+
+        let edges = vec![vec![0, 1, 2], vec![3, 0, 4], vec![5, 6, 0]];
+
+        assert_eq!(*root.shortest().unwrap(), Dist::max());
+
+        unsafe {
+            root.recalc(&vertices, &edges, &mut Stats::default());
+            assert_eq!(
+                *root
+                    .recalc_descendants(1, &vertices, &edges, &mut Stats::default())
+                    .unwrap(),
+                Dist(10)
+            );
+        }
+
+        assert_eq!(*root.shortest().unwrap(), Dist(10));
+
+        root.children[0].mask();
+
+        assert_eq!(*root.shortest().unwrap(), Dist(11));
+    }
+
+    #[test]
+    fn fill_vertices_test() {
+        let root = Node::root(4, 2, 0, Dist(0));
+
+        let mut vertices = vec![LinkedList::new(); 5];
+        root.fill(&mut vertices);
+
+        assert_eq!(vertices[0].len(), 1);
+        assert_eq!(vertices[1].len(), 4);
+        assert_eq!(vertices[2].len(), 4);
+        assert_eq!(vertices[3].len(), 4);
+        assert_eq!(vertices[4].len(), 4);
+
+        for (key, vertices) in vertices.iter().enumerate() {
+            for vertex in vertices.iter() {
+                unsafe {
+                    assert_eq!(vertex.as_ref().key, key);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fill_edges_test() {
+        let root = Node::root(4, 2, 0, Dist(0));
+
+        let mut edges = vec![vec![LinkedList::new(); 5]; 5];
+        root.fill(&mut edges);
+
+        assert_eq!(edges[0][0].len(), 0);
+        assert_eq!(edges[0][1].len(), 1);
+        assert_eq!(edges[0][2].len(), 1);
+        assert_eq!(edges[0][3].len(), 1);
+        assert_eq!(edges[0][4].len(), 1);
+        assert_eq!(edges[1][0].len(), 3);
+        assert_eq!(edges[1][1].len(), 0);
+        assert_eq!(edges[1][2].len(), 1);
+        assert_eq!(edges[1][3].len(), 1);
+        assert_eq!(edges[1][4].len(), 1);
+        assert_eq!(edges[2][0].len(), 3);
         assert_eq!(edges[2][1].len(), 1);
         assert_eq!(edges[2][2].len(), 0);
         assert_eq!(edges[2][3].len(), 1);
@@ -462,9 +1522,9 @@ mod tests {
         assert_eq!(edges[4][3].len(), 1);
         assert_eq!(edges[4][4].len(), 0);
 
-        for i in 0..edges.len() {
-            for key in 0..edges[i].len() {
-                for edge in edges[i][key].iter() {
+        for edges in edges.iter() {
+            for (key, edges) in edges.iter().enumerate() {
+                for edge in edges.iter() {
                     unsafe {
                         assert_eq!(edge.as_ref().key, key);
                     }
@@ -482,7 +1542,100 @@ mod tests {
         let edges = vec![vec![0, 1, 2], vec![3, 0, 4], vec![5, 6, 0]];
 
         unsafe {
-            assert_eq!(*root.recalc(&vertices, &edges), Dist(10));
+            assert_eq!(
+                *root
+                    .recalc(&vertices, &edges, &mut Stats::default())
+                    .unwrap(),
+                Dist(10)
+            );
         }
     }
+
+    #[test]
+    fn validate_test() {
+        let mut root = Node::root(2, 2, 0, Dist(0));
+
+        let mut graph = Graph::new(2);
+        graph.edges = vec![vec![0, 1, 2], vec![3, 0, 4], vec![5, 6, 0]];
+
+        let mut mismatches = Vec::new();
+        root.validate(&Dist(0), 0, &graph, &mut mismatches);
+
+        assert_eq!(
+            mismatches
+                .iter()
+                .filter(|mismatch| matches!(mismatch, Mismatch::Value(..)))
+                .count(),
+            6
+        );
+        assert!(mismatches.contains(&Mismatch::Front(vec![0], vec![], vec![vec![0, 1, 2, 0]])));
+
+        unsafe {
+            root.recalc(&graph.vertices, &graph.edges, &mut Stats::default());
+        }
+        let mut mismatches = Vec::new();
+        root.validate(&Dist(0), 0, &graph, &mut mismatches);
+
+        assert!(mismatches.is_empty());
+
+        root.children[1].children[0].val = Dist(0);
+        let mut mismatches = Vec::new();
+        root.validate(&Dist(0), 0, &graph, &mut mismatches);
+
+        assert_eq!(
+            mismatches,
+            vec![Mismatch::Value(vec![0, 2, 1], &Dist(0), Dist(8))]
+        );
+
+        root.children[1].children[0].val = Dist(8);
+        root.children[1].masked = 1;
+        root.children[0].front.clear();
+        let mut mismatches = Vec::new();
+        root.validate(&Dist(0), 0, &graph, &mut mismatches);
+
+        assert_eq!(
+            mismatches,
+            vec![
+                Mismatch::Front(vec![0, 1], vec![], vec![vec![0, 1, 2, 0]]),
+                Mismatch::Masked(vec![0, 2], 1, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn best_test() {
+        let mut root = Node::root(2, 2, 0, Dist(0));
+
+        assert!(root.best().is_none());
+
+        let vertices = vec![0, 0, 0];
+
+        let edges = vec![vec![0, 1, 2], vec![3, 0, 4], vec![5, 6, 0]];
+
+        unsafe {
+            root.recalc(&vertices, &edges, &mut Stats::default());
+        }
+
+        assert_eq!(root.best().unwrap().keys(), [0, 1, 2, 0]);
+        assert_eq!(root.children[0].best().unwrap().val, Dist(10));
+        assert_eq!(root.children[1].best().unwrap().val, Dist(11));
+
+        let child = NonNull::from(&*root.children[0].children[0]);
+        unsafe {
+            (*child.as_ptr()).mask();
+            Node::propagate(&[child]);
+        }
+
+        assert_eq!(root.best().unwrap().keys(), [0, 2, 1, 0]);
+        assert!(root.children[0].best().is_none());
+
+        let edges = vec![vec![0, 1, 2], vec![10, 0, 4], vec![5, 6, 0]];
+        let child = NonNull::from(&*root.children[1].children[0].children[0]);
+        unsafe {
+            (*child.as_ptr()).recalc(&vertices, &edges, &mut Stats::default());
+            Node::propagate(&[child]);
+        }
+
+        assert_eq!(root.best().unwrap().val, Dist(2 + 6 + 10));
+    }
 }
@@ -0,0 +1,124 @@
+use crate::max::Max;
+use crate::recalc::Recalc;
+use std::cmp::Ordering;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Pareto<A, B>(pub A, pub B);
+
+impl<A, B> PartialOrd for Pareto<A, B>
+where
+    A: PartialOrd,
+    B: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.0.partial_cmp(&other.0)?, self.1.partial_cmp(&other.1)?) {
+            (Ordering::Equal, ordering) | (ordering, Ordering::Equal) => Some(ordering),
+            (Ordering::Less, Ordering::Less) => Some(Ordering::Less),
+            (Ordering::Greater, Ordering::Greater) => Some(Ordering::Greater),
+            _ => None,
+        }
+    }
+}
+
+impl<A, B> Max for Pareto<A, B>
+where
+    A: Max,
+    B: Max,
+{
+    fn max() -> Self {
+        Self(A::max(), B::max())
+    }
+
+    fn is_feasible(&self) -> bool {
+        self.0.is_feasible() && self.1.is_feasible()
+    }
+}
+
+impl<VA, VB, EA, EB, A, B> Recalc<(VA, VB), (EA, EB)> for Pareto<A, B>
+where
+    A: Recalc<VA, EA>,
+    B: Recalc<VB, EB>,
+{
+    fn recalc(&self, vertex: &(VA, VB), edge: &(EA, EB)) -> Self {
+        Self(
+            self.0.recalc(&vertex.0, &edge.0),
+            self.1.recalc(&vertex.1, &edge.1),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::update_edges;
+    use crate::Dist;
+    use crate::PlacementsTree;
+
+    #[test]
+    fn partial_cmp_test() {
+        assert_eq!(
+            Pareto(1, 2).partial_cmp(&Pareto(1, 2)),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            Pareto(1, 2).partial_cmp(&Pareto(1, 3)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            Pareto(2, 3).partial_cmp(&Pareto(1, 3)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            Pareto(0, 0).partial_cmp(&Pareto(1, 1)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(Pareto(0, 2).partial_cmp(&Pareto(1, 1)), None);
+        assert_eq!(Pareto(0.0, 1.0).partial_cmp(&Pareto(f64::NAN, 1.0)), None);
+    }
+
+    #[test]
+    fn front_test() {
+        let mut ptree: PlacementsTree<(i64, i64), (i64, i64), _> =
+            PlacementsTree::new(2, 2, 0, Pareto(Dist(0), Dist(0)));
+        update_edges(&mut ptree, |v, u| ((v * 4 + u) as i64, (u * 4 + v) as i64));
+
+        assert_eq!(
+            ptree.front(),
+            [(vec![0, 1, 2, 0], &Pareto(Dist(1 + 6 + 8), Dist(4 + 9 + 2)))]
+        );
+        assert_eq!(*ptree.shortest().unwrap(), Pareto(Dist(15), Dist(15)));
+
+        ptree.update_edge(1, 2, (6, 0));
+
+        assert_eq!(
+            ptree.front(),
+            [(vec![0, 1, 2, 0], &Pareto(Dist(1 + 6 + 8), Dist(4 + 2)))]
+        );
+
+        ptree.update_edge(0, 2, (0, 8));
+
+        assert_eq!(
+            ptree.front(),
+            [
+                (vec![0, 1, 2, 0], &Pareto(Dist(1 + 6 + 8), Dist(4 + 2))),
+                (vec![0, 2, 1, 0], &Pareto(Dist(9 + 4), Dist(8 + 6 + 1))),
+            ]
+        );
+        assert_eq!(ptree.shortest(), None);
+        assert_eq!(ptree.shortest_path(), None);
+        assert_eq!(ptree.shortest_len(2), None);
+        assert_eq!(
+            *ptree.update_edge(1, 2, (5, 0)).unwrap(),
+            Pareto(Dist(1 + 5 + 8), Dist(4 + 2))
+        );
+        assert_eq!(ptree.shortest(), None);
+        assert_eq!(
+            *ptree.update_edge(0, 1, (0, 4)).unwrap(),
+            Pareto(Dist(5 + 8), Dist(4 + 2))
+        );
+        assert_eq!(
+            ptree.front(),
+            [(vec![0, 1, 2, 0], &Pareto(Dist(5 + 8), Dist(4 + 2)))]
+        );
+    }
+}
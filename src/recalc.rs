@@ -1,3 +1,45 @@
 pub trait Recalc<V, E> {
     fn recalc(&self, vertex: &V, edge: &E) -> Self;
 }
+
+pub trait RecalcAt<V, E> {
+    fn recalc_at(
+        &self,
+        depth: usize,
+        from: usize,
+        to: usize,
+        vertex: &V,
+        edge: &E,
+        next: &V,
+    ) -> Self;
+}
+
+pub trait TryRecalc<V, E>: Sized {
+    fn try_recalc(&self, vertex: &V, edge: &E) -> Option<Self>;
+}
+
+impl<V, E, D> RecalcAt<V, E> for D
+where
+    D: Recalc<V, E>,
+{
+    fn recalc_at(
+        &self,
+        _depth: usize,
+        _from: usize,
+        _to: usize,
+        vertex: &V,
+        edge: &E,
+        _next: &V,
+    ) -> Self {
+        self.recalc(vertex, edge)
+    }
+}
+
+impl<V, E, X> Recalc<V, E> for Option<X>
+where
+    X: TryRecalc<V, E>,
+{
+    fn recalc(&self, vertex: &V, edge: &E) -> Self {
+        self.as_ref().and_then(|x| x.try_recalc(vertex, edge))
+    }
+}
@@ -0,0 +1,183 @@
+use crate::max::Max;
+use crate::node::{dominate, least};
+use crate::recalc::RecalcAt;
+use crate::PlacementsTree;
+
+pub fn placements<V, E, D>(ptree: &PlacementsTree<V, E, D>) -> Vec<(Vec<usize>, D)>
+where
+    D: RecalcAt<V, E> + Max + Clone,
+{
+    let mut placed = vec![false; ptree.n() + 1];
+    placed[ptree.key()] = true;
+    let mut path = vec![ptree.key()];
+    let mut placements = Vec::new();
+    enumerate(ptree, &mut path, &mut placed, &mut placements);
+    placements
+}
+
+pub fn shortest<V, E, D>(ptree: &PlacementsTree<V, E, D>) -> Option<(Vec<usize>, D)>
+where
+    D: RecalcAt<V, E> + PartialOrd + Max + Clone,
+{
+    let mut front = Vec::new();
+    for placement in placements(ptree) {
+        dominate(&mut front, placement, |(_, val)| val);
+    }
+    least(front)
+}
+
+fn enumerate<V, E, D>(
+    ptree: &PlacementsTree<V, E, D>,
+    path: &mut Vec<usize>,
+    placed: &mut [bool],
+    placements: &mut Vec<(Vec<usize>, D)>,
+) where
+    D: RecalcAt<V, E> + Max + Clone,
+{
+    let constraints = ptree.tree.constraints();
+    let len = path.len() - 1;
+    if len >= ptree.min_k() {
+        let end = ptree.end().filter(|&end| !placed[end]);
+        let allowed = end.is_none_or(|end| constraints.allows(end, placed));
+        if let Some(end) = end {
+            placed[end] = true;
+        }
+        if allowed && constraints.missing(placed) == 0 {
+            let mut path = path.clone();
+            path.extend(ptree.end());
+            if let Some(val) = evaluate(ptree, &path) {
+                placements.push((path, val));
+            }
+        }
+        if let Some(end) = end {
+            placed[end] = false;
+        }
+    }
+    if len < ptree.k() {
+        for v in 0..placed.len() {
+            if !placed[v] && Some(v) != ptree.end() && constraints.allows(v, placed) {
+                placed[v] = true;
+                path.push(v);
+                enumerate(ptree, path, placed, placements);
+                path.pop();
+                placed[v] = false;
+            }
+        }
+    }
+}
+
+fn evaluate<V, E, D>(ptree: &PlacementsTree<V, E, D>, path: &[usize]) -> Option<D>
+where
+    D: RecalcAt<V, E> + Max + Clone,
+{
+    let graph = &ptree.graph;
+    if path.iter().any(|&v| graph.disabled[v]) {
+        return None;
+    }
+    let mut val = ptree.root_value().clone();
+    for (depth, pair) in path.windows(2).enumerate() {
+        let (v, u) = (pair[0], pair[1]);
+        if graph.forbidden[v][u] {
+            return None;
+        }
+        let (vertex, edge, next) = (&graph.vertices[v], &graph.edges[v][u], &graph.vertices[u]);
+        val = val.recalc_at(depth + 1, v, u, vertex, edge, next);
+    }
+    (path.len() == 1 || val.is_feasible()).then_some(val)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::update_edges;
+    use crate::Builder;
+
+    fn check<V, E, D>(ptree: &PlacementsTree<V, E, D>)
+    where
+        D: RecalcAt<V, E> + PartialOrd + Max + Clone + std::fmt::Debug,
+    {
+        assert_eq!(ptree.validate(), vec![]);
+        assert_eq!(
+            ptree.shortest(),
+            shortest(ptree).as_ref().map(|(_, val)| val)
+        );
+    }
+
+    #[test]
+    fn placements_test() {
+        let mut ptree: PlacementsTree<i64, i64, i64> = PlacementsTree::new(2, 2, 0, 0);
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as i64);
+
+        assert_eq!(
+            placements(&ptree),
+            vec![(vec![0, 1, 2, 0], 1 + 6 + 8), (vec![0, 2, 1, 0], 2 + 9 + 4)]
+        );
+        assert_eq!(shortest(&ptree), Some((vec![0, 1, 2, 0], 1 + 6 + 8)));
+    }
+
+    #[test]
+    fn updates_test() {
+        let trees = [
+            Builder::new(4, 3, 0).build(0),
+            Builder::new(4, 3, 1).min_k(1).build(0),
+            Builder::new(4, 3, 0).end(4).build(0),
+            Builder::new(4, 4, 0).min_k(0).open().build(0),
+            Builder::new(4, 3, 0).precedence([(1, 2), (3, 4)]).build(0),
+            Builder::new(4, 3, 0)
+                .end(4)
+                .precedence([(0, 3), (2, 1)])
+                .build(0),
+            Builder::new(4, 3, 0).min_k(1).required([2, 3]).build(0),
+            Builder::new(4, 3, 0).symmetric().build(0),
+        ];
+        for mut ptree in trees {
+            check(&ptree);
+            let mut seed = 7u64;
+            let mut next = |bound: usize| {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (seed >> 33) as usize % bound
+            };
+            update_edges(&mut ptree, |_, _| next(10) as i64);
+            check(&ptree);
+            for _ in 0..200 {
+                let n = ptree.n();
+                let (v, u) = (next(n + 1), next(n + 1));
+                let val = next(20) as i64 - 5;
+                match next(4) {
+                    0 => {
+                        ptree.update_vertex(v, val);
+                    }
+                    1 | 2 if v != u => {
+                        ptree.update_edge(v, u, val);
+                    }
+                    3 if v != u && u != ptree.key() && Some(u) != ptree.end() => {
+                        if ptree.is_disabled(u) {
+                            ptree.enable_vertex(u);
+                        } else {
+                            ptree.disable_vertex(u);
+                        }
+                    }
+                    _ if v != u => {
+                        if ptree.is_forbidden(v, u) {
+                            ptree.allow_edge(v, u);
+                        } else {
+                            ptree.forbid_edge(v, u);
+                        }
+                    }
+                    _ => {}
+                }
+                check(&ptree);
+            }
+            ptree.set_k(2);
+            check(&ptree);
+            if ptree.is_symmetric() {
+                ptree.add_symmetric_vertex(1, [3; 5]);
+            } else {
+                ptree.add_vertex(1, [3; 5], [2; 5]);
+            }
+            check(&ptree);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct Stats {
+    pub seeded: usize,
+    pub recalced: usize,
+    pub leaves: usize,
+    pub elapsed: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PlacementsTree;
+
+    #[test]
+    fn stats_test() {
+        let mut ptree: PlacementsTree<i64, i64, i64> = PlacementsTree::new(2, 2, 0, 0);
+        assert_eq!(*ptree.stats(), Stats::default());
+
+        ptree.update_edge(0, 1, 1);
+        let stats = ptree.stats();
+        assert_eq!((stats.seeded, stats.recalced, stats.leaves), (1, 3, 1));
+
+        ptree.update_vertex(1, 1);
+        let stats = ptree.stats();
+        assert_eq!((stats.seeded, stats.recalced, stats.leaves), (2, 5, 2));
+
+        ptree.update_outgoing(0, [(1, 1), (2, 1)]);
+        let stats = ptree.stats();
+        assert_eq!((stats.seeded, stats.recalced, stats.leaves), (2, 6, 2));
+
+        ptree.set_k(1);
+        let stats = ptree.stats();
+        assert_eq!((stats.seeded, stats.recalced, stats.leaves), (2, 2, 2));
+
+        ptree.add_vertex(0, [0, 0, 0], [0, 0, 0]);
+        let stats = ptree.stats();
+        assert_eq!((stats.seeded, stats.recalced, stats.leaves), (1, 2, 1));
+    }
+}
@@ -0,0 +1,19 @@
+use crate::apply::Apply;
+use crate::max::Max;
+use crate::recalc::RecalcAt;
+use crate::PlacementsTree;
+
+pub fn update_edges<V, E, D, Diff, F>(ptree: &mut PlacementsTree<V, E, D>, mut diff: F)
+where
+    F: FnMut(usize, usize) -> Diff,
+    E: Apply<Diff> + Clone,
+    D: RecalcAt<V, E> + PartialOrd + Max,
+{
+    for v in 0..=ptree.n() {
+        for u in 0..=ptree.n() {
+            if v != u {
+                ptree.update_edge(v, u, diff(v, u));
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use crate::max::Max;
+use crate::recalc::RecalcAt;
+use std::ops::Add;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Window<T> {
+    pub ready: T,
+    pub due: T,
+    pub service: T,
+}
+
+impl<T> Window<T> {
+    pub fn new(ready: T, due: T, service: T) -> Self {
+        Self {
+            ready,
+            due,
+            service,
+        }
+    }
+}
+
+impl<T> Default for Window<T>
+where
+    T: Default + Max,
+{
+    fn default() -> Self {
+        Self {
+            ready: T::default(),
+            due: T::max(),
+            service: T::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct TimeWindowed<T> {
+    pub time: T,
+}
+
+impl<T> TimeWindowed<T> {
+    pub fn new(time: T) -> Self {
+        Self { time }
+    }
+}
+
+impl<T> Max for TimeWindowed<T>
+where
+    T: Max + PartialOrd,
+{
+    fn max() -> Self {
+        Self { time: T::max() }
+    }
+
+    fn is_feasible(&self) -> bool {
+        self.time < T::max()
+    }
+}
+
+impl<T> TimeWindowed<T>
+where
+    T: Add<Output = T> + PartialOrd + Copy + Max,
+{
+    fn arrive(&self, edge: &T, next: &Window<T>) -> Self {
+        if !self.is_feasible() {
+            return Self::max();
+        }
+        let arrival = self.time + *edge;
+        if arrival > next.due {
+            Self::max()
+        } else {
+            let start = if arrival < next.ready {
+                next.ready
+            } else {
+                arrival
+            };
+            Self {
+                time: start + next.service,
+            }
+        }
+    }
+}
+
+macro_rules! recalc_at {
+    ($($t:ty),*) => {
+        $(
+            impl RecalcAt<Window<$t>, $t> for TimeWindowed<$t> {
+                fn recalc_at(
+                    &self,
+                    _depth: usize,
+                    _from: usize,
+                    _to: usize,
+                    _vertex: &Window<$t>,
+                    edge: &$t,
+                    next: &Window<$t>,
+                ) -> Self {
+                    self.arrive(edge, next)
+                }
+            }
+        )*
+    };
+}
+
+recalc_at!(i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, isize, usize, f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::update_edges;
+    use crate::{Builder, PlacementsTree};
+
+    #[test]
+    fn recalc_test() {
+        let val = TimeWindowed::new(5);
+        let recalc =
+            |val: TimeWindowed<i64>, next| val.recalc_at(1, 0, 1, &Window::default(), &1, &next);
+
+        assert_eq!(recalc(val, Window::default()), TimeWindowed::new(6));
+        assert_eq!(recalc(val, Window::new(10, 20, 2)), TimeWindowed::new(12));
+        assert_eq!(recalc(val, Window::new(0, 6, 2)), TimeWindowed::new(8));
+        assert_eq!(recalc(val, Window::new(0, 5, 2)), TimeWindowed::max());
+        assert_eq!(
+            recalc(TimeWindowed::max(), Window::default()),
+            TimeWindowed::max()
+        );
+        assert!(!TimeWindowed::<i64>::max().is_feasible());
+    }
+
+    #[test]
+    fn time_windowed_test() {
+        let mut ptree: PlacementsTree<Window<i64>, i64, TimeWindowed<i64>> =
+            PlacementsTree::new(2, 2, 0, TimeWindowed::new(0));
+        update_edges(&mut ptree, |_, _| 1);
+        ptree.update_vertex(1, Window::new(10, 20, 2));
+        ptree.update_vertex(2, Window::new(0, 5, 1));
+
+        assert_eq!(*ptree.shortest().unwrap(), TimeWindowed::new(10 + 2 + 1));
+        assert_eq!(ptree.shortest_path().unwrap().0, [0, 2, 1, 0]);
+        assert_eq!(
+            *ptree.update_vertex(0, Window::new(0, 13, 0)).unwrap(),
+            TimeWindowed::new(10 + 2 + 1)
+        );
+        assert_eq!(ptree.update_vertex(0, Window::new(0, 12, 0)), None);
+        assert_eq!(ptree.shortest(), None);
+    }
+
+    #[test]
+    fn time_windowed_symmetric_test() {
+        let mut ptree: PlacementsTree<Window<i64>, i64, TimeWindowed<i64>> = Builder::new(2, 2, 0)
+            .symmetric()
+            .build(TimeWindowed::new(0));
+        ptree.update_edge(0, 1, 1);
+        ptree.update_edge(0, 2, 1);
+        ptree.update_edge(1, 2, 1);
+        ptree.update_vertex(1, Window::new(10, 20, 2));
+        ptree.update_vertex(2, Window::new(0, 5, 1));
+
+        assert_eq!(*ptree.shortest().unwrap(), TimeWindowed::new(10 + 2 + 1));
+        assert_eq!(ptree.shortest_path().unwrap().0, [0, 2, 1, 0]);
+        assert_eq!(ptree.validate(), vec![]);
+    }
+
+    #[test]
+    fn time_windowed_open_test() {
+        let mut ptree: PlacementsTree<Window<i64>, i64, TimeWindowed<i64>> =
+            Builder::new(2, 2, 0).open().build(TimeWindowed::new(0));
+        update_edges(&mut ptree, |_, _| 1);
+        ptree.update_vertex(1, Window::new(10, 20, 2));
+
+        assert_eq!(*ptree.shortest().unwrap(), TimeWindowed::new(10 + 2));
+        assert_eq!(ptree.shortest_path().unwrap().0, [0, 2, 1]);
+        assert_eq!(
+            *ptree.update_vertex(1, Window::new(0, 1, 2)).unwrap(),
+            TimeWindowed::new(1 + 2 + 1)
+        );
+        assert_eq!(ptree.shortest_path().unwrap().0, [0, 1, 2]);
+        assert_eq!(ptree.update_vertex(2, Window::new(0, 3, 0)), None);
+        assert_eq!(ptree.shortest(), None);
+    }
+}
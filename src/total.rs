@@ -0,0 +1,112 @@
+use crate::max::Max;
+use crate::recalc::Recalc;
+use std::cmp::Ordering;
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Total<T>(pub T);
+
+impl<T> Max for Total<T>
+where
+    T: Max,
+{
+    fn max() -> Self {
+        Self(T::max())
+    }
+
+    fn is_feasible(&self) -> bool {
+        self.0.is_feasible()
+    }
+}
+
+macro_rules! total {
+    ($($t:ty),*) => {
+        $(
+            impl PartialEq for Total<$t> {
+                fn eq(&self, other: &Self) -> bool {
+                    self.cmp(other) == Ordering::Equal
+                }
+            }
+
+            impl Eq for Total<$t> {}
+
+            impl PartialOrd for Total<$t> {
+                fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl Ord for Total<$t> {
+                fn cmp(&self, other: &Self) -> Ordering {
+                    self.0.total_cmp(&other.0)
+                }
+            }
+
+            impl Recalc<$t, $t> for $t {
+                fn recalc(&self, vertex: &$t, edge: &$t) -> Self {
+                    self + vertex + edge
+                }
+            }
+
+            impl Recalc<$t, $t> for Total<$t> {
+                fn recalc(&self, vertex: &$t, edge: &$t) -> Self {
+                    Self(self.0.recalc(vertex, edge))
+                }
+            }
+        )*
+    };
+}
+
+total!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::update_edges;
+    use crate::PlacementsTree;
+
+    #[test]
+    fn cmp_test() {
+        assert!(Total(1.0) < Total(2.0));
+        assert!(Total(f64::NEG_INFINITY) < Total(-1.0));
+        assert!(Total(f64::INFINITY) < Total(f64::NAN));
+        assert_eq!(Total(f32::NAN), Total(f32::NAN));
+        assert_eq!(Total(f64::NAN).cmp(&Total(0.0)), Ordering::Greater);
+    }
+
+    #[test]
+    fn recalc_test() {
+        assert_eq!(1.0f64.recalc(&0.5, &0.25), 1.75);
+        assert_eq!(<f64 as Max>::max().recalc(&-1.0, &-1.0), f64::INFINITY);
+        assert!(1.0f64.is_feasible());
+        assert!(!f64::INFINITY.is_feasible());
+        assert!(!f64::NAN.is_feasible());
+        assert!(!Total(f32::NAN).is_feasible());
+        assert!(Total(1.0f32).recalc(&1.0, &f32::NAN).0.is_nan());
+    }
+
+    #[test]
+    fn float_test() {
+        let mut ptree: PlacementsTree<f64, f64, f64> = PlacementsTree::new(2, 2, 0, 0.0);
+        assert_eq!(*ptree.shortest().unwrap(), 0.0);
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as f64 / 2.0);
+
+        assert_eq!(*ptree.shortest().unwrap(), 7.5);
+        assert_eq!(ptree.update_edge(1, 2, f64::NAN), None);
+        assert_eq!(*ptree.shortest().unwrap(), 7.5);
+        assert_eq!(ptree.update_edge(2, 1, f64::NAN), None);
+        assert_eq!(ptree.shortest(), None);
+    }
+
+    #[test]
+    fn total_test() {
+        let mut ptree: PlacementsTree<f32, f32, Total<f32>> =
+            PlacementsTree::new(2, 2, 0, Total(0.0));
+        update_edges(&mut ptree, |v, u| (v * 4 + u) as f32);
+
+        assert_eq!(*ptree.shortest().unwrap(), Total(15.0));
+        assert_eq!(*ptree.update_edge(0, 1, -1.0).unwrap(), Total(13.0));
+        assert_eq!(ptree.update_vertex(2, f32::NAN), None);
+        assert_eq!(ptree.shortest(), None);
+        assert_eq!(*ptree.update_vertex(2, 0.0).unwrap(), Total(13.0));
+    }
+}
@@ -0,0 +1,33 @@
+#[cfg(feature = "tracing")]
+macro_rules! span {
+    ($name:literal $(, $field:ident = $val:expr)* $(; $($empty:ident),*)?) => {
+        tracing::debug_span!(
+            $name
+            $(, $field = $val)*
+            $($(, $empty = tracing::field::Empty)*)?
+        )
+        .entered()
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! span {
+    ($name:literal $(, $field:ident = $val:expr)* $(; $($empty:ident),*)?) => {
+        crate::trace::Span
+    };
+}
+
+pub(crate) use span;
+
+#[cfg(feature = "tracing")]
+pub type Span = tracing::span::EnteredSpan;
+
+#[cfg(not(feature = "tracing"))]
+pub struct Span;
+
+#[cfg(not(feature = "tracing"))]
+impl Span {
+    pub fn record<T>(&self, _field: &str, _val: T) -> &Self {
+        self
+    }
+}
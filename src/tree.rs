@@ -0,0 +1,385 @@
+use crate::constraints::Constraints;
+use crate::fill::Fill;
+use crate::graph::Graph;
+use crate::node::{limit, Mismatch, Node};
+use crate::recalc::RecalcAt;
+use crate::stats::Stats;
+use crate::trace;
+use crate::Max;
+use std::collections::{HashSet, LinkedList};
+use std::ptr::NonNull;
+
+pub struct Tree<D> {
+    root: Box<Node<D>>,
+    vertices_idx: Vec<LinkedList<NonNull<Node<D>>>>,
+    edges_idx: Vec<Vec<LinkedList<NonNull<Node<D>>>>>,
+    k: usize,
+    min_k: Option<usize>,
+    key: usize,
+    end: Option<usize>,
+    constraints: Constraints,
+}
+
+impl<D> Tree<D> {
+    pub fn new<V, E>(
+        k: usize,
+        min_k: Option<usize>,
+        key: usize,
+        end: Option<usize>,
+        constraints: Constraints,
+        val: D,
+        graph: &Graph<V, E>,
+    ) -> Self
+    where
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        let n = graph.n;
+        assert!(key <= n);
+        let k = k.min(limit(n, key, end));
+        let mut tree = Self {
+            root: Node::path(n, k, min_k.unwrap_or(k), key, end, &constraints, val),
+            vertices_idx: Vec::new(),
+            edges_idx: Vec::new(),
+            k,
+            min_k,
+            key,
+            end,
+            constraints,
+        };
+        tree.reindex(n);
+        unsafe {
+            tree.root
+                .recalc(&graph.vertices, &graph.edges, &mut Stats::default());
+        }
+        tree
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn min_k(&self) -> usize {
+        self.min_k.map_or(self.k, |min_k| min_k.min(self.k))
+    }
+
+    pub fn key(&self) -> usize {
+        self.key
+    }
+
+    pub fn end(&self) -> Option<usize> {
+        self.end
+    }
+
+    pub fn root_value(&self) -> &D {
+        self.root.val()
+    }
+
+    pub fn constraints(&self) -> &Constraints {
+        &self.constraints
+    }
+
+    pub fn validate<V, E>(&self, graph: &Graph<V, E>) -> Vec<Mismatch<'_, D>>
+    where
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        let mut mismatches = Vec::new();
+        self.root.validate(
+            self.root.val(),
+            graph.disabled[self.key] as usize,
+            graph,
+            &mut mismatches,
+        );
+        mismatches
+    }
+
+    pub fn shortest(&self) -> Option<&D>
+    where
+        D: PartialOrd,
+    {
+        self.root.best().map(Node::val)
+    }
+
+    pub fn shortest_path(&self) -> Option<(Vec<usize>, &D)>
+    where
+        D: PartialOrd,
+    {
+        self.root.best().map(|node| (node.keys(), node.val()))
+    }
+
+    pub fn shortest_len(&self, len: usize) -> Option<&D>
+    where
+        D: PartialOrd,
+    {
+        self.root.shortest_at(len + self.end.is_some() as usize)
+    }
+
+    pub fn front(&self) -> Vec<(Vec<usize>, &D)>
+    where
+        D: PartialOrd,
+    {
+        self.root
+            .front()
+            .into_iter()
+            .map(|node| (node.keys(), node.val()))
+            .collect()
+    }
+
+    pub fn recalc_vertex<V, E>(
+        &mut self,
+        v: usize,
+        graph: &Graph<V, E>,
+        stats: &mut Stats,
+    ) -> Option<&D>
+    where
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        let vertices = (self.key == v)
+            .then(|| NonNull::from(&*self.root))
+            .into_iter()
+            .chain(
+                self.edges_idx
+                    .iter()
+                    .flat_map(|edges| edges[v].iter().copied()),
+            )
+            .collect::<Vec<_>>();
+        let recalced = vertices.iter().copied().collect::<HashSet<_>>();
+        let vertices = vertices
+            .into_iter()
+            .filter(|vertex| unsafe { !vertex.as_ref().descends(&recalced) })
+            .collect::<Vec<_>>();
+        for vertex in vertices.iter() {
+            stats.seeded += 1;
+            unsafe {
+                (*vertex.as_ptr()).recalc(&graph.vertices, &graph.edges, stats);
+            }
+        }
+        unsafe {
+            Node::propagate(&vertices);
+            Node::best_of(&vertices).map(Node::val)
+        }
+    }
+
+    pub fn recalc_edge<V, E>(
+        &mut self,
+        v: usize,
+        u: usize,
+        graph: &Graph<V, E>,
+        stats: &mut Stats,
+    ) -> Option<&D>
+    where
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        let edges = self.edges_idx[v][u].iter().copied().collect::<Vec<_>>();
+        for edge in edges.iter() {
+            stats.seeded += 1;
+            unsafe {
+                (*edge.as_ptr()).recalc(&graph.vertices, &graph.edges, stats);
+            }
+        }
+        unsafe {
+            Node::propagate(&edges);
+            Node::best_of(&edges).map(Node::val)
+        }
+    }
+
+    pub fn recalc_edges<V, E>(
+        &mut self,
+        edges: &[(usize, usize)],
+        graph: &Graph<V, E>,
+        stats: &mut Stats,
+    ) -> Option<&D>
+    where
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        let recalced = edges
+            .iter()
+            .flat_map(|&(v, u)| self.edges_idx[v][u].iter().copied())
+            .collect::<HashSet<_>>();
+        let edges = edges
+            .iter()
+            .flat_map(|&(v, u)| self.edges_idx[v][u].iter().copied())
+            .filter(|edge| unsafe { !edge.as_ref().descends(&recalced) })
+            .collect::<Vec<_>>();
+        for edge in edges.iter() {
+            stats.seeded += 1;
+            unsafe {
+                (*edge.as_ptr()).recalc(&graph.vertices, &graph.edges, stats);
+            }
+        }
+        unsafe {
+            Node::propagate(&edges);
+            Node::best_of(&edges).map(Node::val)
+        }
+    }
+
+    pub fn insert<V, E>(&mut self, graph: &Graph<V, E>, stats: &mut Stats) -> Option<&D>
+    where
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        let n = graph.n;
+        let min_k = self.min_k();
+        self.constraints.push();
+        let inserted = self.root.insert(n, self.end, self.k, &self.constraints);
+        self.vertices_idx.push(LinkedList::new());
+        for edges in self.edges_idx.iter_mut() {
+            edges.push(LinkedList::new());
+        }
+        self.edges_idx.push(vec![LinkedList::new(); n + 1]);
+        for node in inserted.iter() {
+            stats.seeded += 1;
+            unsafe {
+                let parent = node.as_ref().parent().unwrap();
+                if parent.children().len() == 1 {
+                    self.vertices_idx[parent.key()].push_back(NonNull::from(parent));
+                }
+                let node = &mut *node.as_ptr();
+                node.complete(self.end, min_k, self.k, &self.constraints);
+                node.fill(&mut self.vertices_idx);
+                node.fill(&mut self.edges_idx);
+                node.remask(&graph.disabled, &graph.forbidden);
+                node.recalc(&graph.vertices, &graph.edges, stats);
+            }
+        }
+        unsafe {
+            Node::propagate(&inserted);
+            Node::best_of(&inserted).map(Node::val)
+        }
+    }
+
+    pub fn set_k<V, E>(&mut self, k: usize, graph: &Graph<V, E>, stats: &mut Stats) -> Option<&D>
+    where
+        D: RecalcAt<V, E> + PartialOrd + Max,
+    {
+        let k = k.min(limit(graph.n, self.key, self.end));
+        let depth = if self.constraints.has_required() {
+            0
+        } else {
+            self.k.min(k)
+        };
+        if k > self.k {
+            self.root
+                .extend(self.end, self.k, k - self.k, &self.constraints);
+        } else if k < self.k {
+            self.root.truncate(self.end, k, &self.constraints);
+        }
+        if k != self.k {
+            self.k = k;
+            self.root
+                .complete(self.end, self.min_k(), k, &self.constraints);
+            self.reindex(graph.n);
+            self.root.remask(&graph.disabled, &graph.forbidden);
+            unsafe {
+                self.root
+                    .recalc_descendants(depth, &graph.vertices, &graph.edges, stats);
+            }
+            self.root.refresh_all();
+        }
+        self.shortest()
+    }
+
+    pub fn mask_vertex(&mut self, v: usize)
+    where
+        D: PartialOrd,
+    {
+        let vertices = self
+            .edges_idx
+            .iter()
+            .flat_map(|edges| edges[v].iter().copied())
+            .collect::<Vec<_>>();
+        Self::remark(&vertices, Node::mask);
+    }
+
+    pub fn unmask_vertex(&mut self, v: usize)
+    where
+        D: PartialOrd,
+    {
+        let vertices = self
+            .edges_idx
+            .iter()
+            .flat_map(|edges| edges[v].iter().copied())
+            .collect::<Vec<_>>();
+        Self::remark(&vertices, Node::unmask);
+    }
+
+    pub fn mask_edge(&mut self, v: usize, u: usize)
+    where
+        D: PartialOrd,
+    {
+        let edges = self.edges_idx[v][u].iter().copied().collect::<Vec<_>>();
+        Self::remark(&edges, Node::mask);
+    }
+
+    pub fn unmask_edge(&mut self, v: usize, u: usize)
+    where
+        D: PartialOrd,
+    {
+        let edges = self.edges_idx[v][u].iter().copied().collect::<Vec<_>>();
+        Self::remark(&edges, Node::unmask);
+    }
+
+    fn remark(nodes: &[NonNull<Node<D>>], mark: fn(&mut Node<D>))
+    where
+        D: PartialOrd,
+    {
+        unsafe {
+            for node in nodes.iter() {
+                mark(&mut *node.as_ptr());
+            }
+            Node::propagate(nodes);
+        }
+    }
+
+    fn reindex(&mut self, n: usize) {
+        {
+            let span = trace::span!("fill_vertices", n = n; nodes);
+            self.vertices_idx = vec![LinkedList::new(); n + 1];
+            self.root.fill(&mut self.vertices_idx);
+            let nodes: usize = self.vertices_idx.iter().map(LinkedList::len).sum();
+            span.record("nodes", nodes);
+        }
+        let span = trace::span!("fill_edges", n = n; nodes);
+        self.edges_idx = vec![vec![LinkedList::new(); n + 1]; n + 1];
+        self.root.fill(&mut self.edges_idx);
+        let nodes: usize = self.edges_idx.iter().flatten().map(LinkedList::len).sum();
+        span.record("nodes", nodes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Dist;
+
+    #[test]
+    fn insert_test() {
+        for (k, min_k, end) in [(2, None, Some(0)), (3, Some(1), Some(0)), (3, None, None)] {
+            let mut graph: Graph<i64, i64> = Graph::new(3);
+            let mut tree = Tree::new(k, min_k, 0, end, Constraints::new(3), Dist(0), &graph);
+            graph.add_vertex(0, [1; 4], [2; 4]);
+            tree.insert(&graph, &mut Stats::default());
+
+            let sets = |idx: &[LinkedList<NonNull<Node<Dist<i64>>>>]| {
+                idx.iter()
+                    .map(|nodes| nodes.iter().copied().collect::<HashSet<_>>())
+                    .collect::<Vec<_>>()
+            };
+            let vertices = sets(&tree.vertices_idx);
+            let edges = tree
+                .edges_idx
+                .iter()
+                .map(|edges| sets(edges))
+                .collect::<Vec<_>>();
+            tree.reindex(graph.n);
+
+            assert_eq!(vertices, sets(&tree.vertices_idx));
+            assert_eq!(
+                edges,
+                tree.edges_idx
+                    .iter()
+                    .map(|edges| sets(edges))
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(tree.validate(&graph), vec![]);
+        }
+    }
+}